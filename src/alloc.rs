@@ -0,0 +1,149 @@
+//! Allocation accounting, enabled by the `alloc-stats` feature.
+//!
+//! The feature installs `Counting` as the global allocator, which counts the
+//! allocations of every thread separately, so solvers running in parallel don't
+//! disturb each other. Without the feature `measure` doesn't count anything.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Whether allocations are counted
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+/// The allocations of a measured function
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of allocations, including reallocations
+    pub count: usize,
+    /// Bytes requested by all allocations
+    pub bytes: usize,
+    /// The highest amount of memory in use at once, above what was in use before
+    pub peak: usize,
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    count: usize,
+    bytes: usize,
+    current: isize,
+    peak: isize,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            count: 0,
+            bytes: 0,
+            current: 0,
+            peak: 0,
+        })
+    };
+}
+
+fn update<F: FnOnce(&mut Counters)>(func: F) {
+    // the counters are gone while the thread shuts down
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        func(&mut c);
+        c.peak = c.peak.max(c.current);
+        counters.set(c);
+    });
+}
+
+/// A global allocator counting the allocations of the current thread
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        update(|c| {
+            c.count += 1;
+            c.bytes += layout.size();
+            c.current += layout.size() as isize;
+        });
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        update(|c| c.current -= layout.size() as isize);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        update(|c| {
+            c.count += 1;
+            c.bytes += layout.size();
+            c.current += layout.size() as isize;
+        });
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        update(|c| {
+            c.count += 1;
+            c.bytes += new_size;
+            c.current += new_size as isize - layout.size() as isize;
+        });
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Run `func` and count its allocations on the current thread,
+/// the statistics are `None` unless the `alloc-stats` feature is enabled
+pub fn measure<F, T>(func: F) -> (T, Option<Stats>)
+where
+    F: FnOnce() -> T,
+{
+    if !ENABLED {
+        return (func(), None);
+    }
+
+    let mut before = COUNTERS.with(Cell::get);
+    // the peak of an enclosing measurement must not be lost
+    let outer_peak = before.peak;
+    before.peak = before.current;
+    COUNTERS.with(|counters| counters.set(before));
+
+    let result = func();
+
+    let mut after = COUNTERS.with(Cell::get);
+    let stats = Stats {
+        count: after.count - before.count,
+        bytes: after.bytes - before.bytes,
+        peak: (after.peak - before.current).max(0) as usize,
+    };
+    after.peak = after.peak.max(outer_peak);
+    COUNTERS.with(|counters| counters.set(after));
+
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counted() {
+        let (sum, stats) = measure(|| {
+            let big = vec![0u8; 4096];
+            let small: Vec<u64> = Vec::with_capacity(16);
+            drop(big);
+            let grown: Vec<u8> = (0..10u8).collect();
+            small.capacity() + grown.len()
+        });
+        assert_eq!(sum, 26);
+        assert_eq!(stats.is_some(), ENABLED);
+
+        if let Some(stats) = stats {
+            assert!(stats.count >= 3);
+            assert!(stats.bytes >= 4096 + 128 + 10);
+            assert!(stats.peak >= 4096 + 128);
+            assert!(stats.peak < stats.bytes);
+        }
+
+        assert_eq!(measure(|| 1 + 1).1.map(|s| s.count), ENABLED.then_some(0));
+    }
+}
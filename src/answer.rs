@@ -0,0 +1,126 @@
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
+use std::str::FromStr;
+
+/// The answer of a single part.
+///
+/// Integers are always stored as `Integer` if they fit into an `i64`
+/// and text containing line breaks is always stored as `Multiline`,
+/// so answers can be compared no matter how they were created.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// The part has not been solved yet
+    Unsolved,
+    Integer(i64),
+    /// Unsigned integers too big for `Integer`
+    BigUint(u128),
+    /// A single line of text
+    Text(String),
+    /// Several lines of text, e.g. ASCII art
+    Multiline(String),
+}
+
+impl Answer {
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Self::Unsolved)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsolved => f.pad("unsolved"),
+            Self::Integer(i) => i.fmt(f),
+            Self::BigUint(u) => u.fmt(f),
+            Self::Text(s) | Self::Multiline(s) => f.pad(s),
+        }
+    }
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Self::Integer(i64::from(value))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    match i64::try_from(value) {
+                        Ok(value) => Self::Integer(value),
+                        Err(_) => Self::BigUint(value as u128),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_signed!(i8, i16, i32, i64);
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        if text.contains('\n') {
+            Self::Multiline(text)
+        } else {
+            Self::Text(text)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        text.to_string().into()
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Parse integers as integers, everything else as text
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(i) = s.parse::<i64>() {
+            Ok(Self::Integer(i))
+        } else if let Ok(u) = s.parse::<u128>() {
+            Ok(Self::BigUint(u))
+        } else {
+            Ok(s.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized() {
+        assert_eq!(Answer::from(42usize), Answer::from(42i32));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigUint(u64::MAX as u128));
+        assert_eq!("42".parse(), Ok(Answer::from(42u8)));
+        assert_eq!("-7".parse(), Ok(Answer::Integer(-7)));
+        assert_eq!("18446744073709551615".parse(), Ok(Answer::from(u64::MAX)));
+        assert_eq!("abc".parse(), Ok(Answer::Text("abc".to_string())));
+        assert_eq!(
+            "#.#\n.#.".parse(),
+            Ok(Answer::Multiline("#.#\n.#.".to_string()))
+        );
+        assert_ne!(Answer::from(""), Answer::Unsolved);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{:>5}", Answer::from(42)), "   42");
+        assert_eq!(format!("{:>5}", Answer::from("ab")), "   ab");
+        assert_eq!(Answer::Unsolved.to_string(), "unsolved");
+    }
+}
@@ -0,0 +1,121 @@
+use crate::input::Source;
+use crate::records;
+use crate::Answer;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the answers manifest inside an inputs directory
+pub const MANIFEST: &str = "answers";
+
+/// The manifest belonging to the embedded inputs
+const EMBEDDED: &str = include_str!("inputs/answers");
+
+/// The expected answers for one set of inputs.
+///
+/// The manifest is a record file (see [`records`]) of `<day> <part> <answer>` triples.
+/// Line breaks in multi-line answers are written as `\n`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers(HashMap<(usize, usize), Answer>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Unknown,
+}
+
+impl FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = HashMap::new();
+
+        for (n, line) in records::records(s) {
+            let entry: Vec<_> = line.splitn(3, char::is_whitespace).collect();
+            let (day, part, answer) = match &entry[..] {
+                [day, part, answer] => (day.parse(), part.parse(), answer.trim()),
+                _ => return Err(format!("line {}: expected '<day> <part> <answer>'", n)),
+            };
+
+            match (day, part) {
+                (Ok(day), Ok(part)) => {
+                    let answer: Answer = answer
+                        .replace("\\n", "\n")
+                        .parse()
+                        .unwrap_or_else(|e| match e {});
+                    if answers.insert((day, part), answer).is_some() {
+                        return Err(format!("line {}: duplicate answer", n));
+                    }
+                }
+                _ => return Err(format!("line {}: invalid day or part", n)),
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+}
+
+impl Answers {
+    /// Load the manifest from `path`, or the one belonging to `source`.
+    /// A missing manifest next to the inputs results in an empty manifest.
+    pub fn load(source: &Source, path: Option<&Path>) -> Result<Self, String> {
+        let (path, optional): (PathBuf, _) = match (path, source) {
+            (Some(path), _) => (path.into(), false),
+            (None, Source::Embedded) => return EMBEDDED.parse(),
+            (None, Source::Directory(dir)) => (dir.join(MANIFEST), true),
+            (None, _) => return Ok(Self::default()),
+        };
+
+        match records::load(&path)? {
+            Some(answers) => Ok(answers),
+            None if optional => Ok(Self::default()),
+            None => Err(format!("Can't read {}: not found", path.display())),
+        }
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&Answer> {
+        self.0.get(&(day, part))
+    }
+
+    /// Compare `answer` with the expected answer of `day` and `part`
+    pub fn check(&self, day: usize, part: usize, answer: &Answer) -> Status {
+        match self.get(day, part) {
+            _ if !answer.is_solved() => Status::Unknown,
+            Some(expected) if expected == answer => Status::Pass,
+            Some(_) => Status::Fail,
+            None => Status::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let answers: Answers = "# comment\n\n1 1 42\n1 2 hello world\n2 1 #.\\n.#\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(answers.get(1, 1), Some(&Answer::Integer(42)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::from("hello world")));
+        assert_eq!(answers.get(2, 1), Some(&Answer::from("#.\n.#")));
+        assert_eq!(answers.check(1, 1, &42usize.into()), Status::Pass);
+        assert_eq!(answers.check(1, 1, &43usize.into()), Status::Fail);
+        assert_eq!(answers.check(1, 1, &"42".into()), Status::Fail);
+        assert_eq!(answers.check(1, 1, &Answer::Unsolved), Status::Unknown);
+        assert_eq!(answers.check(2, 2, &42usize.into()), Status::Unknown);
+
+        assert!("1 1".parse::<Answers>().is_err());
+        assert!("x 1 42".parse::<Answers>().is_err());
+        assert!("1 1 42\n1 1 43".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn embedded() {
+        let answers = Answers::load(&Source::Embedded, None).unwrap();
+        assert_eq!(answers.get(7, 1), Some(&Answer::Integer(151)));
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+/// How long a single part is benchmarked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Budget {
+    /// Run exactly this many times
+    Runs(usize),
+    /// Run until the accumulated run time exceeds the budget (at least once)
+    Time(Duration),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub warmup: usize,
+    pub budget: Budget,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            budget: Budget::Time(Duration::from_millis(500)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Calculate the statistics of the given samples, `None` if there are none
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let min = *samples.first()?;
+        let median = if n % 2 == 0 {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        let mean = samples.iter().sum::<Duration>() / n as u32;
+        let variance = if n > 1 {
+            samples
+                .iter()
+                .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        // nearest-rank percentile
+        let p95 = samples[(n * 95 + 99) / 100 - 1];
+
+        Some(Stats {
+            runs: n,
+            min,
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            p95,
+        })
+    }
+}
+
+/// Measure `func` according to `config` and return the duration of every run
+pub fn measure<F, T>(config: &Config, mut func: F) -> Vec<Duration>
+where
+    F: FnMut() -> T,
+{
+    for _ in 0..config.warmup {
+        std::hint::black_box(func());
+    }
+
+    let mut samples = Vec::new();
+    let mut total = Duration::default();
+
+    loop {
+        let done = match config.budget {
+            Budget::Runs(runs) => samples.len() >= runs,
+            Budget::Time(budget) => !samples.is_empty() && total >= budget,
+        };
+        if done {
+            break samples;
+        }
+
+        let start = Instant::now();
+        std::hint::black_box(func());
+        let elapsed = start.elapsed();
+
+        total += elapsed;
+        samples.push(elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn us(us: &[u64]) -> Vec<Duration> {
+        us.iter().copied().map(Duration::from_micros).collect()
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::new(&us(&[4, 2, 8, 6])).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_micros(2));
+        assert_eq!(stats.median, Duration::from_micros(5));
+        assert_eq!(stats.mean, Duration::from_micros(5));
+        assert_eq!(stats.p95, Duration::from_micros(8));
+        assert_eq!(stats.stddev.as_nanos(), 2582);
+
+        let stats = Stats::new(&us(&(1..=100).collect::<Vec<_>>())).unwrap();
+        assert_eq!(stats.median, Duration::from_nanos(50_500));
+        assert_eq!(stats.p95, Duration::from_micros(95));
+
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn budget() {
+        let mut calls = 0;
+        let config = Config {
+            warmup: 2,
+            budget: Budget::Runs(5),
+        };
+        assert_eq!(measure(&config, || calls += 1).len(), 5);
+        assert_eq!(calls, 7);
+
+        let config = Config {
+            warmup: 0,
+            budget: Budget::Time(Duration::default()),
+        };
+        assert_eq!(measure(&config, || ()).len(), 1);
+    }
+}
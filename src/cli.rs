@@ -0,0 +1,1103 @@
+use aoc_rs::bench::{self, Budget};
+use aoc_rs::input::Source;
+use aoc_rs::perf;
+use aoc_rs::report::Format;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: aoc-rs [COMMAND] [OPTIONS]
+
+Commands:
+    run [DAYS...]      Run the given days (default: all days)
+    verify [DAYS...]   Run the given days and compare the answers with
+                       the answers manifest, fails on any mismatch
+    bench [DAYS...]    Benchmark the given days
+    compare [DAYS...]  Compare the latest recorded benchmark with a baseline,
+                       fails if a part got slower than the threshold
+    list               List the available days
+    new <DAY> [TITLE]  Generate the module, an empty input and an examples file
+                       for a new day
+    fetch <DAY>        Download the input of a day unless it is cached in the
+                       inputs directory (default: $AOC_INPUTS, falling back to
+                       src/inputs), the session token is read from $AOC_SESSION
+                       or ~/.config/aoc-rs/session
+    submit <DAY> <PART>
+                       Solve a part and submit the answer, answers which are
+                       known to be wrong are never submitted
+    watch <DAY>        Run and verify a day whenever its input changes and
+                       show how the answers changed
+    debug              Debug the boot code of day 8 interactively
+    trace              Trace the boot code of day 8 until it terminates or
+                       loops
+    lint               Check the boot code of day 8 for unreachable code,
+                       infinite loops and jumps out of bounds without running it
+    help               Print this help
+
+Days can be given as a single day (7), a range (3..5, 3..=5)
+or a comma separated list (1,4,7).
+
+Options:
+    -a, --all            Run all days
+    -p, --part <PART>    Only run the given part (1 or 2)
+    -i, --input <FILE>   Read the input of a single day from FILE (- for stdin)
+    -I, --inputs <DIR>   Read the inputs from DIR/<day> (default: $AOC_INPUTS,
+                         falling back to the inputs embedded in the binary)
+    --answers <FILE>     Read the expected answers from FILE
+                         (default: the manifest next to the inputs)
+    -s, --set <NAME>     Run the named input set in inputs/NAME (or $AOC_SETS/NAME),
+                         may be given several times
+    --all-sets           Run every named input set, each one is verified
+                         against the manifest in its directory
+    -f, --format <FMT>   Output format of run and verify: text, json or csv
+                         (default: text)
+    -j, --jobs <N>       Run and verify up to N days in parallel
+                         (default: 1, 0 uses all cores)
+    -t, --timeout <S>    Give up on a part after S seconds, the remaining days
+                         are still run (default: 30, 0 waits forever)
+    -h, --help           Print this help
+
+Bench options:
+    --runs <N>           Run every part N times
+    --time <MS>          Run every part for MS milliseconds (default: 500)
+    --warmup <N>         Number of unmeasured runs before measuring (default: 3)
+    --record             Append the results to the benchmark history
+    --history <FILE>     The benchmark history (default: bench-history in the crate)
+
+Compare options:
+    --baseline <COMMIT>  Compare with the latest run on COMMIT
+                         (default: the run before the latest one)
+    --threshold <PCT>    Flag parts whose median got more than PCT percent
+                         slower (default: 10)
+    --history <FILE>     The benchmark history (default: bench-history in the crate)
+
+Fetch options:
+    -I, --inputs <DIR>   Cache the input in DIR/<day>
+    --url <URL>          Download from URL/day/<day>/input
+                         (default: $AOC_URL or https://adventofcode.com/2020)
+
+Submit options:
+    -i, --input <FILE>   Solve the input in FILE (- for stdin)
+    -I, --inputs <DIR>   Solve the input in DIR/<day>
+    --url <URL>          Submit to URL/day/<day>/answer (default: as for fetch)
+    --history <FILE>     Record every submission in FILE (default: history in
+                         the inputs directory, submit-history in the crate)
+
+Watch options:
+    -p, --part <PART>    Only run the given part (1 or 2)
+    -i, --input <FILE>   Watch the input in FILE
+    -I, --inputs <DIR>   Watch the input in DIR/<day> (default: $AOC_INPUTS,
+                         falling back to src/inputs)
+    --answers <FILE>     Read the expected answers from FILE
+    -e, --examples       Also watch and check the examples in src/examples/<day>
+    --interval <MS>      Check for changes every MS milliseconds (default: 500)
+    -t, --timeout <S>    Give up on a part after S seconds (default: 30)
+
+Debug, trace and lint options:
+    -i, --input <FILE>   Use the program in FILE
+    -I, --inputs <DIR>   Use the program in DIR/8
+    -f, --format <FMT>   Format of the trace: text or json (default: text)
+    --stats              Print summary statistics instead of the trace
+    --loops <N>          Follow a loop N times before the trace stops
+                         (default: 1, with --stats 100)
+
+    --dot                Print the control-flow graph in the DOT language of
+                         Graphviz instead of linting";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    List,
+    Run(Selection),
+    Verify(Selection),
+    /// The results are appended to the given history
+    Bench(Selection, bench::Config, Option<PathBuf>),
+    Compare {
+        days: Vec<usize>,
+        baseline: Option<String>,
+        threshold: usize,
+        history: PathBuf,
+    },
+    New {
+        day: usize,
+        title: String,
+    },
+    Fetch {
+        day: usize,
+        inputs: Option<PathBuf>,
+        url: Option<String>,
+    },
+    Submit {
+        day: usize,
+        part: usize,
+        source: Source,
+        url: Option<String>,
+        history: Option<PathBuf>,
+    },
+    Watch {
+        day: usize,
+        parts: Vec<usize>,
+        source: Source,
+        answers: Option<PathBuf>,
+        examples: bool,
+        interval: Duration,
+        timeout: Option<Duration>,
+    },
+    /// Debug the day 8 program from the source
+    Debug(Source),
+    /// Trace the day 8 program from the source, or summarise the trace
+    Trace {
+        source: Source,
+        format: Format,
+        stats: bool,
+        /// How often a loop is followed
+        loops: usize,
+    },
+    /// Lint the day 8 program from the source, or print its control-flow graph
+    Lint {
+        source: Source,
+        dot: bool,
+    },
+}
+
+/// The named input sets to run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sets {
+    /// Only the inputs given by the source
+    Default,
+    Named(Vec<String>),
+    All,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub days: Vec<usize>,
+    pub parts: Vec<usize>,
+    pub source: Source,
+    pub sets: Sets,
+    pub answers: Option<PathBuf>,
+    pub format: Format,
+    pub jobs: usize,
+    /// How long a part may run, `None` waits forever
+    pub timeout: Option<Duration>,
+}
+
+/// How long a part may run by default
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a trace follows a loop by default, and when summarising it
+pub const DEFAULT_LOOPS: usize = 1;
+pub const DEFAULT_STATS_LOOPS: usize = 100;
+
+fn parse_timeout(option: &str, value: Option<String>) -> Result<Option<Duration>, String> {
+    match parse_number(option, value)? {
+        0 => Ok(None),
+        secs => Ok(Some(Duration::from_secs(secs as u64))),
+    }
+}
+
+/// Parse a day specification like "7", "3..5", "3..=5" or "1,4,7"
+fn parse_days(spec: &str, available: &[usize]) -> Result<Vec<usize>, String> {
+    let parse_day = |day: &str| -> Result<usize, String> {
+        day.trim()
+            .parse()
+            .map_err(|_| format!("Invalid day '{}'", day))
+    };
+
+    let mut days = Vec::new();
+
+    for spec in spec.split(',') {
+        let range = if let Some((start, end)) = spec.split_once("..=") {
+            parse_day(start)?..=parse_day(end)?
+        } else if let Some((start, end)) = spec.split_once("..") {
+            let (start, end) = (parse_day(start)?, parse_day(end)?);
+            if end == 0 {
+                return Err(format!("Empty range '{}'", spec));
+            }
+            start..=end - 1
+        } else {
+            let day = parse_day(spec)?;
+            day..=day
+        };
+
+        if range.is_empty() {
+            return Err(format!("Empty range '{}'", spec));
+        }
+
+        for day in range {
+            if !available.contains(&day) {
+                let available: Vec<_> = available.iter().map(usize::to_string).collect();
+                return Err(format!(
+                    "Unknown day {} (available: {})",
+                    day,
+                    available.join(", ")
+                ));
+            }
+            days.push(day);
+        }
+    }
+
+    Ok(days)
+}
+
+fn parse_number(option: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("Missing value for {}", option))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, option))
+}
+
+/// Parse the day of a puzzle, which doesn't have to be available
+fn parse_puzzle_day(command: &str, day: Option<String>) -> Result<usize, String> {
+    let day = day.ok_or_else(|| format!("Missing day for {}", command))?;
+    match day.parse() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid day '{}' (expected 1 to 25)", day)),
+    }
+}
+
+/// Parse the arguments of `new`, the day must not exist yet
+fn parse_new<I>(mut args: I, available: &[usize]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let day = parse_puzzle_day("new", args.next())?;
+
+    if available.contains(&day) {
+        return Err(format!("Day {} exists already", day));
+    }
+
+    let title: Vec<_> = args.collect();
+    if let Some(option) = title.iter().find(|arg| arg.starts_with('-')) {
+        return Err(format!("Unknown option '{}'", option));
+    }
+
+    let title = if title.is_empty() {
+        format!("Day {}", day)
+    } else {
+        title.join(" ")
+    };
+
+    Ok(Command::New { day, title })
+}
+
+/// Parse the arguments of `fetch`
+fn parse_fetch<I>(mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let day = parse_puzzle_day("fetch", args.next())?;
+    let mut source = Source::Embedded;
+    let mut url = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            arg if parse_input_flag(arg, &mut args, &mut source)? => (),
+            "--url" => url = Some(args.next().ok_or("Missing value for --url")?),
+            arg if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            arg => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let inputs = match source {
+        Source::Embedded => None,
+        Source::Directory(dir) => Some(dir),
+        _ => return Err("fetch needs an inputs directory (--inputs)".to_string()),
+    };
+
+    Ok(Command::Fetch { day, inputs, url })
+}
+
+/// Parse a single available day
+fn parse_single_day(
+    command: &str,
+    day: Option<String>,
+    available: &[usize],
+) -> Result<usize, String> {
+    match day {
+        Some(day) => match parse_days(&day, available)?[..] {
+            [day] => Ok(day),
+            _ => Err(format!("Expected a single day, not '{}'", day)),
+        },
+        None => Err(format!("Missing day for {}", command)),
+    }
+}
+
+/// Parse the arguments of `submit`
+fn parse_submit<I>(mut args: I, available: &[usize]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let day = parse_single_day("submit", args.next(), available)?;
+    let part = match args.next() {
+        Some(part) => parse_part(Some(part))?[0],
+        None => return Err("Missing part for submit".to_string()),
+    };
+    let mut source = Source::Embedded;
+    let mut url = None;
+    let mut history = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            arg if parse_input_flag(arg, &mut args, &mut source)? => (),
+            "--url" => url = Some(args.next().ok_or("Missing value for --url")?),
+            "--history" => {
+                let path = args.next().ok_or("Missing value for --history")?;
+                history = Some(PathBuf::from(path));
+            }
+            arg if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            arg => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Command::Submit {
+        day,
+        part,
+        source,
+        url,
+        history,
+    })
+}
+
+/// Parse the arguments of `watch`
+fn parse_watch<I>(mut args: I, available: &[usize]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let day = parse_single_day("watch", args.next(), available)?;
+    let mut parts = vec![1, 2];
+    let mut source = Source::Embedded;
+    let mut answers = None;
+    let mut examples = false;
+    let mut interval = 500;
+    let mut timeout = Some(DEFAULT_TIMEOUT);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => parts = parse_part(args.next())?,
+            arg if parse_input_flag(arg, &mut args, &mut source)? => (),
+            "--answers" => {
+                let path = args.next().ok_or("Missing value for --answers")?;
+                answers = Some(PathBuf::from(path));
+            }
+            "-e" | "--examples" => examples = true,
+            option @ "--interval" => match parse_number(option, args.next())? {
+                0 => return Err("--interval must be at least 1".to_string()),
+                ms => interval = ms,
+            },
+            option @ "-t" | option @ "--timeout" => timeout = parse_timeout(option, args.next())?,
+            arg if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            arg => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    if source == Source::Stdin {
+        return Err("stdin can't be watched".to_string());
+    }
+
+    Ok(Command::Watch {
+        day,
+        parts,
+        source,
+        answers,
+        examples,
+        interval: Duration::from_millis(interval as u64),
+        timeout,
+    })
+}
+
+/// Parse the arguments of `debug`, `trace` and `lint`
+fn parse_debug<I>(command: &str, mut args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut source = Source::Embedded;
+    let mut format = Format::Text;
+    let mut stats = false;
+    let mut loops = None;
+    let mut dot = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--format" if command == "trace" => {
+                format = match args.next().ok_or("Missing value for --format")?.parse()? {
+                    Format::Csv => return Err("Traces can't be written as csv".to_string()),
+                    format => format,
+                };
+            }
+            "--stats" if command == "trace" => stats = true,
+            "--loops" if command == "trace" => {
+                loops = match parse_number("--loops", args.next())? {
+                    0 => return Err("A trace has to follow a loop at least once".to_string()),
+                    loops => Some(loops),
+                };
+            }
+            "--dot" if command == "lint" => dot = true,
+            arg if parse_input_flag(arg, &mut args, &mut source)? => (),
+            arg if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            arg => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    // the commands are read from stdin
+    if source == Source::Stdin {
+        return Err("The program can't be read from stdin".to_string());
+    }
+
+    match command {
+        "trace" => Ok(Command::Trace {
+            source,
+            format,
+            stats,
+            loops: loops.unwrap_or(if stats {
+                DEFAULT_STATS_LOOPS
+            } else {
+                DEFAULT_LOOPS
+            }),
+        }),
+        "lint" => Ok(Command::Lint { source, dot }),
+        _ => Ok(Command::Debug(source)),
+    }
+}
+
+/// The benchmark history of the crate the binary was built from
+pub fn default_history() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(perf::HISTORY)
+}
+
+/// Parse the arguments of `compare`
+fn parse_compare<I>(mut args: I, available: &[usize]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut days = Vec::new();
+    let mut baseline = None;
+    let mut threshold = 10;
+    let mut history = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--baseline" => baseline = Some(args.next().ok_or("Missing value for --baseline")?),
+            option @ "--threshold" => threshold = parse_number(option, args.next())?,
+            "--history" => {
+                let path = args.next().ok_or("Missing value for --history")?;
+                history = Some(PathBuf::from(path));
+            }
+            arg if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            spec => days.extend(parse_days(spec, available)?),
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    Ok(Command::Compare {
+        days,
+        baseline,
+        threshold,
+        history: history.unwrap_or_else(default_history),
+    })
+}
+
+fn parse_part(part: Option<String>) -> Result<Vec<usize>, String> {
+    match part.as_deref() {
+        Some("1") => Ok(vec![1]),
+        Some("2") => Ok(vec![2]),
+        Some(part) => Err(format!("Invalid part '{}' (expected 1 or 2)", part)),
+        None => Err("Missing value for --part".to_string()),
+    }
+}
+
+/// Handle `-i/--input` and `-I/--inputs`, returns `false` if `arg` is neither
+fn parse_input_flag<I>(arg: &str, args: &mut I, source: &mut Source) -> Result<bool, String>
+where
+    I: Iterator<Item = String>,
+{
+    match arg {
+        "-i" | "--input" => {
+            *source = match args.next().as_deref() {
+                Some("-") => Source::Stdin,
+                Some(path) => Source::File(PathBuf::from(path)),
+                None => return Err("Missing value for --input".to_string()),
+            }
+        }
+        "-I" | "--inputs" => {
+            let dir = args.next().ok_or("Missing value for --inputs")?;
+            *source = Source::Directory(PathBuf::from(dir));
+        }
+        _ => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+/// Parse the command line arguments (without the program name).
+/// `available` are the numbers of the available days in ascending order.
+pub fn parse<I>(args: I, available: &[usize]) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+
+    let command = match args.peek().map(String::as_str) {
+        Some("help") | Some("-h") | Some("--help") => return Ok(Command::Help),
+        Some("list") => return Ok(Command::List),
+        Some("new") => {
+            args.next();
+            return parse_new(args, available);
+        }
+        Some("fetch") => {
+            args.next();
+            return parse_fetch(args);
+        }
+        Some("submit") => {
+            args.next();
+            return parse_submit(args, available);
+        }
+        Some("compare") => {
+            args.next();
+            return parse_compare(args, available);
+        }
+        Some("watch") => {
+            args.next();
+            return parse_watch(args, available);
+        }
+        Some(command @ "debug") | Some(command @ "trace") | Some(command @ "lint") => {
+            let command = command.to_string();
+            args.next();
+            return parse_debug(&command, args);
+        }
+        Some(command @ "run") | Some(command @ "verify") | Some(command @ "bench") => {
+            let command = command.to_string();
+            args.next();
+            command
+        }
+        Some(arg) if !arg.starts_with('-') => {
+            return Err(format!("Unknown command '{}'", arg));
+        }
+        _ => "run".to_string(),
+    };
+
+    let mut days = Vec::new();
+    let mut parts = vec![1, 2];
+    let mut all = false;
+    let mut source = Source::Embedded;
+    let mut answers = None;
+    let mut sets = Sets::Default;
+    let mut bench = bench::Config::default();
+    let mut record = false;
+    let mut history = None;
+    let mut format = Format::Text;
+    let mut jobs = 1;
+    let mut timeout = Some(DEFAULT_TIMEOUT);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-a" | "--all" => all = true,
+            "-p" | "--part" => parts = parse_part(args.next())?,
+            arg if parse_input_flag(arg, &mut args, &mut source)? => (),
+            "--answers" => {
+                let path = args.next().ok_or("Missing value for --answers")?;
+                answers = Some(PathBuf::from(path));
+            }
+            "-s" | "--set" if command != "bench" => {
+                let name = args.next().ok_or("Missing value for --set")?;
+                sets = match sets {
+                    Sets::Default => Sets::Named(vec![name]),
+                    Sets::Named(mut names) => {
+                        names.push(name);
+                        Sets::Named(names)
+                    }
+                    Sets::All => return Err("--set can't be combined with --all-sets".to_string()),
+                };
+            }
+            "--all-sets" if command != "bench" => {
+                if let Sets::Named(_) = sets {
+                    return Err("--set can't be combined with --all-sets".to_string());
+                }
+                sets = Sets::All;
+            }
+            "-f" | "--format" if command != "bench" => {
+                format = args.next().ok_or("Missing value for --format")?.parse()?;
+            }
+            option @ "-j" | option @ "--jobs" if command != "bench" => {
+                jobs = parse_number(option, args.next())?;
+            }
+            option @ "-t" | option @ "--timeout" if command != "bench" => {
+                timeout = parse_timeout(option, args.next())?;
+            }
+            option @ "--runs" | option @ "--time" | option @ "--warmup" if command == "bench" => {
+                let value = parse_number(option, args.next())?;
+                match option {
+                    "--runs" if value == 0 => return Err("--runs must be at least 1".to_string()),
+                    "--runs" => bench.budget = Budget::Runs(value),
+                    "--time" => bench.budget = Budget::Time(Duration::from_millis(value as u64)),
+                    _ => bench.warmup = value,
+                }
+            }
+            "--record" if command == "bench" => record = true,
+            "--history" if command == "bench" => {
+                let path = args.next().ok_or("Missing value for --history")?;
+                history = Some(PathBuf::from(path));
+            }
+            arg if arg.starts_with('-') => return Err(format!("Unknown option '{}'", arg)),
+            spec => days.extend(parse_days(spec, available)?),
+        }
+    }
+
+    if all || days.is_empty() {
+        days = available.to_vec();
+    } else {
+        days.sort_unstable();
+        days.dedup();
+    }
+
+    if matches!(source, Source::File(_) | Source::Stdin) && days.len() != 1 {
+        return Err("--input requires exactly one day".to_string());
+    }
+
+    if sets != Sets::Default && source != Source::Embedded {
+        return Err("Input sets can't be combined with --input or --inputs".to_string());
+    }
+
+    if history.is_some() && !record {
+        return Err("--history requires --record".to_string());
+    }
+
+    let several_sets = match &sets {
+        Sets::Default => false,
+        Sets::Named(names) => names.len() > 1,
+        Sets::All => true,
+    };
+    if several_sets && answers.is_some() {
+        return Err("--answers can't be used with several input sets".to_string());
+    }
+
+    let selection = Selection {
+        days,
+        parts,
+        source,
+        sets,
+        answers,
+        format,
+        jobs,
+        timeout,
+    };
+
+    Ok(match command.as_str() {
+        "verify" => Command::Verify(selection),
+        "bench" => {
+            let history = if record {
+                Some(history.unwrap_or_else(default_history))
+            } else {
+                None
+            };
+            Command::Bench(selection, bench, history)
+        }
+        _ => Command::Run(selection),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS: &[usize] = &[1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    fn run(days: Vec<usize>, parts: Vec<usize>) -> Result<Command, String> {
+        Ok(Command::Run(Selection {
+            days,
+            parts,
+            source: Source::Embedded,
+            sets: Sets::Default,
+            answers: None,
+            format: Format::Text,
+            jobs: 1,
+            timeout: Some(DEFAULT_TIMEOUT),
+        }))
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(parse(args(""), DAYS), run(DAYS.to_vec(), vec![1, 2]));
+        assert_eq!(parse(args("run"), DAYS), run(DAYS.to_vec(), vec![1, 2]));
+        assert_eq!(
+            parse(args("run --all"), DAYS),
+            run(DAYS.to_vec(), vec![1, 2])
+        );
+        assert_eq!(parse(args("help"), DAYS), Ok(Command::Help));
+        assert_eq!(parse(args("list"), DAYS), Ok(Command::List));
+        assert_eq!(parse(args("run 7 --help"), DAYS), Ok(Command::Help));
+        assert!(matches!(
+            parse(args("verify 7"), DAYS),
+            Ok(Command::Verify(_))
+        ));
+        assert!(matches!(
+            parse(args("bench 7"), DAYS),
+            Ok(Command::Bench(_, config, None)) if config == bench::Config::default()
+        ));
+    }
+
+    #[test]
+    fn days_and_parts() {
+        assert_eq!(parse(args("run 7 --part 2"), DAYS), run(vec![7], vec![2]));
+        assert_eq!(
+            parse(args("run 3..=5"), DAYS),
+            run(vec![3, 4, 5], vec![1, 2])
+        );
+        assert_eq!(parse(args("run 3..5 -p 1"), DAYS), run(vec![3, 4], vec![1]));
+        assert_eq!(
+            parse(args("run 9,1,4 4"), DAYS),
+            run(vec![1, 4, 9], vec![1, 2])
+        );
+        assert_eq!(parse(args("run 8..10"), DAYS), run(vec![8, 9], vec![1, 2]));
+    }
+
+    #[test]
+    fn inputs() {
+        let source = |args| match parse(args, DAYS) {
+            Ok(Command::Run(selection)) => Some(selection.source),
+            _ => None,
+        };
+
+        assert_eq!(
+            source(self::args("run 7 --input mine")),
+            Some(Source::File("mine".into()))
+        );
+        assert_eq!(source(self::args("run 7 -i -")), Some(Source::Stdin));
+        assert_eq!(
+            source(self::args("run -I inputs")),
+            Some(Source::Directory("inputs".into()))
+        );
+        assert_eq!(source(self::args("run 3..=5 --input mine")), None);
+        assert_eq!(source(self::args("run 7 --input")), None);
+    }
+
+    #[test]
+    fn format() {
+        let format = |args| match parse(args, DAYS) {
+            Ok(Command::Run(selection)) | Ok(Command::Verify(selection)) => Some(selection.format),
+            _ => None,
+        };
+
+        assert_eq!(format(self::args("run")), Some(Format::Text));
+        assert_eq!(format(self::args("run -f csv")), Some(Format::Csv));
+        assert_eq!(
+            format(self::args("verify --format json")),
+            Some(Format::Json)
+        );
+    }
+
+    #[test]
+    fn jobs() {
+        let jobs = |args| match parse(args, DAYS) {
+            Ok(Command::Run(selection)) | Ok(Command::Verify(selection)) => Some(selection.jobs),
+            _ => None,
+        };
+
+        assert_eq!(jobs(self::args("run")), Some(1));
+        assert_eq!(jobs(self::args("run -j 4")), Some(4));
+        assert_eq!(jobs(self::args("verify --jobs 0")), Some(0));
+        assert_eq!(jobs(self::args("run --jobs")), None);
+    }
+
+    #[test]
+    fn timeout() {
+        let timeout = |args| match parse(args, DAYS) {
+            Ok(Command::Run(selection)) | Ok(Command::Verify(selection)) => Ok(selection.timeout),
+            Ok(Command::Watch { timeout, .. }) => Ok(timeout),
+            _ => Err(()),
+        };
+
+        assert_eq!(timeout(self::args("run")), Ok(Some(DEFAULT_TIMEOUT)));
+        assert_eq!(
+            timeout(self::args("verify -t 5")),
+            Ok(Some(Duration::from_secs(5)))
+        );
+        assert_eq!(timeout(self::args("run --timeout 0")), Ok(None));
+        assert_eq!(timeout(self::args("watch 8 -t 0")), Ok(None));
+        assert_eq!(timeout(self::args("run --timeout x")), Err(()));
+        assert!(parse(self::args("bench --timeout 5"), DAYS).is_err());
+    }
+
+    #[test]
+    fn bench_options() {
+        let config = |args| match parse(args, DAYS) {
+            Ok(Command::Bench(_, config, _)) => Some(config),
+            _ => None,
+        };
+
+        assert_eq!(
+            config(self::args("bench 7 --runs 10 --warmup 0")),
+            Some(bench::Config {
+                warmup: 0,
+                budget: Budget::Runs(10)
+            })
+        );
+        assert_eq!(
+            config(self::args("bench --time 100")).map(|c| c.budget),
+            Some(Budget::Time(Duration::from_millis(100)))
+        );
+        assert_eq!(config(self::args("bench --runs 0")), None);
+        assert_eq!(config(self::args("bench --runs x")), None);
+        assert!(parse(self::args("run --runs 10"), DAYS).is_err());
+        assert!(parse(self::args("bench --format json"), DAYS).is_err());
+        assert!(parse(self::args("bench --jobs 4"), DAYS).is_err());
+
+        let record = |args| match parse(args, DAYS) {
+            Ok(Command::Bench(_, _, record)) => Ok(record),
+            Err(e) => Err(e),
+            _ => Err(String::new()),
+        };
+        assert_eq!(
+            record(self::args("bench --record")),
+            Ok(Some(default_history()))
+        );
+        assert_eq!(
+            record(self::args("bench --record --history hist")),
+            Ok(Some("hist".into()))
+        );
+        assert!(record(self::args("bench --history hist")).is_err());
+        assert!(parse(self::args("run --record"), DAYS).is_err());
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(
+            parse(args("compare"), DAYS),
+            Ok(Command::Compare {
+                days: vec![],
+                baseline: None,
+                threshold: 10,
+                history: default_history()
+            })
+        );
+        assert_eq!(
+            parse(
+                args("compare 9,8 --baseline abc123 --threshold 5 --history hist"),
+                DAYS
+            ),
+            Ok(Command::Compare {
+                days: vec![8, 9],
+                baseline: Some("abc123".to_string()),
+                threshold: 5,
+                history: "hist".into()
+            })
+        );
+        assert!(parse(args("compare --threshold x"), DAYS).is_err());
+        assert!(parse(args("compare --part 1"), DAYS).is_err());
+    }
+
+    #[test]
+    fn input_sets() {
+        let sets = |args| match parse(args, DAYS) {
+            Ok(Command::Run(selection)) | Ok(Command::Verify(selection)) => Some(selection.sets),
+            _ => None,
+        };
+
+        assert_eq!(sets(self::args("run")), Some(Sets::Default));
+        assert_eq!(
+            sets(self::args("run --set alice -s bob")),
+            Some(Sets::Named(vec!["alice".to_string(), "bob".to_string()]))
+        );
+        assert_eq!(sets(self::args("verify --all-sets")), Some(Sets::All));
+        assert_eq!(
+            sets(self::args("verify -s alice --answers mine")),
+            Some(Sets::Named(vec!["alice".to_string()]))
+        );
+        assert_eq!(sets(self::args("verify --all-sets --answers mine")), None);
+        assert_eq!(sets(self::args("run -s alice -s bob --answers mine")), None);
+        assert_eq!(sets(self::args("run -s alice --all-sets")), None);
+        assert_eq!(sets(self::args("run -s alice -I inputs")), None);
+        assert_eq!(sets(self::args("run 7 -i - --all-sets")), None);
+        assert_eq!(sets(self::args("run --set")), None);
+        assert!(parse(self::args("bench --all-sets"), DAYS).is_err());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            parse(args("new 10"), DAYS),
+            Ok(Command::New {
+                day: 10,
+                title: "Day 10".to_string()
+            })
+        );
+        assert_eq!(
+            parse(args("new 10 Adapter Array"), DAYS),
+            Ok(Command::New {
+                day: 10,
+                title: "Adapter Array".to_string()
+            })
+        );
+        assert!(parse(args("new"), DAYS).is_err());
+        assert!(parse(args("new 7"), DAYS).is_err());
+        assert!(parse(args("new 26"), DAYS).is_err());
+        assert!(parse(args("new 10 --all"), DAYS).is_err());
+    }
+
+    #[test]
+    fn fetch() {
+        assert_eq!(
+            parse(args("fetch 7"), DAYS),
+            Ok(Command::Fetch {
+                day: 7,
+                inputs: None,
+                url: None
+            })
+        );
+        assert_eq!(
+            parse(args("fetch 12 -I inputs --url http://localhost:8080"), DAYS),
+            Ok(Command::Fetch {
+                day: 12,
+                inputs: Some("inputs".into()),
+                url: Some("http://localhost:8080".to_string())
+            })
+        );
+        assert!(parse(args("fetch"), DAYS).is_err());
+        assert!(parse(args("fetch 0"), DAYS).is_err());
+        assert!(parse(args("fetch 7 8"), DAYS).is_err());
+        assert!(parse(args("fetch 7 --url"), DAYS).is_err());
+        assert!(parse(args("fetch 7 -i input"), DAYS).is_err());
+    }
+
+    #[test]
+    fn submit() {
+        assert_eq!(
+            parse(args("submit 8 2 -I inputs --history hist"), DAYS),
+            Ok(Command::Submit {
+                day: 8,
+                part: 2,
+                source: Source::Directory("inputs".into()),
+                url: None,
+                history: Some("hist".into())
+            })
+        );
+        assert!(matches!(
+            parse(args("submit 8 1 -i - --url http://localhost"), DAYS),
+            Ok(Command::Submit {
+                source: Source::Stdin,
+                url: Some(_),
+                ..
+            })
+        ));
+        assert_eq!(
+            parse(args("submit 8"), DAYS),
+            Err("Missing part for submit".to_string())
+        );
+        assert!(parse(args("submit 8 3"), DAYS).is_err());
+        assert!(parse(args("submit 10 1"), DAYS).is_err());
+        assert!(parse(args("submit 7,8 1"), DAYS).is_err());
+    }
+
+    #[test]
+    fn watch() {
+        assert_eq!(
+            parse(args("watch 8"), DAYS),
+            Ok(Command::Watch {
+                day: 8,
+                parts: vec![1, 2],
+                source: Source::Embedded,
+                answers: None,
+                examples: false,
+                interval: Duration::from_millis(500),
+                timeout: Some(DEFAULT_TIMEOUT),
+            })
+        );
+        assert_eq!(
+            parse(
+                args("watch 8 -p 2 -i in.txt --answers answers -e --interval 100"),
+                DAYS
+            ),
+            Ok(Command::Watch {
+                day: 8,
+                parts: vec![2],
+                source: Source::File(PathBuf::from("in.txt")),
+                answers: Some(PathBuf::from("answers")),
+                examples: true,
+                interval: Duration::from_millis(100),
+                timeout: Some(DEFAULT_TIMEOUT),
+            })
+        );
+        assert!(parse(args("watch"), DAYS).is_err());
+        assert!(parse(args("watch 7..=8"), DAYS).is_err());
+        assert!(parse(args("watch 8 -i -"), DAYS).is_err());
+        assert!(parse(args("watch 8 --interval 0"), DAYS).is_err());
+        assert!(parse(args("watch 8 9"), DAYS).is_err());
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(
+            parse(args("debug"), DAYS),
+            Ok(Command::Debug(Source::Embedded))
+        );
+        assert_eq!(
+            parse(args("debug -i boot"), DAYS),
+            Ok(Command::Debug(Source::File("boot".into())))
+        );
+        assert!(parse(args("debug -i -"), DAYS).is_err());
+        assert!(parse(args("debug 8"), DAYS).is_err());
+        assert!(parse(args("debug --stats"), DAYS).is_err());
+    }
+
+    #[test]
+    fn trace() {
+        assert_eq!(
+            parse(args("trace -I inputs -f json"), DAYS),
+            Ok(Command::Trace {
+                source: Source::Directory("inputs".into()),
+                format: Format::Json,
+                stats: false,
+                loops: DEFAULT_LOOPS,
+            })
+        );
+        assert!(matches!(
+            parse(args("trace --stats"), DAYS),
+            Ok(Command::Trace {
+                stats: true,
+                loops: DEFAULT_STATS_LOOPS,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(args("trace --stats --loops 5"), DAYS),
+            Ok(Command::Trace { loops: 5, .. })
+        ));
+        assert!(parse(args("trace --loops 0"), DAYS).is_err());
+        assert!(parse(args("trace -f csv"), DAYS).is_err());
+    }
+
+    #[test]
+    fn lint() {
+        assert_eq!(
+            parse(args("lint --dot -i boot"), DAYS),
+            Ok(Command::Lint {
+                source: Source::File("boot".into()),
+                dot: true,
+            })
+        );
+        assert!(parse(args("lint --stats"), DAYS).is_err());
+        assert!(parse(args("trace --dot"), DAYS).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse(args("run 10"), DAYS).is_err());
+        assert!(parse(args("run 2..=4"), &[1, 2, 4]).is_err());
+        assert!(parse(args("run 0..=3"), DAYS).is_err());
+        assert!(parse(args("run 3..3"), DAYS).is_err());
+        assert!(parse(args("run 5..=3"), DAYS).is_err());
+        assert!(parse(args("run x"), DAYS).is_err());
+        assert!(parse(args("run 1 --part 3"), DAYS).is_err());
+        assert!(parse(args("run 1 --part"), DAYS).is_err());
+        assert!(parse(args("run 1 --format xml"), DAYS).is_err());
+        assert!(parse(args("run --frobnicate"), DAYS).is_err());
+        assert!(parse(args("frobnicate"), DAYS).is_err());
+    }
+}
@@ -1,32 +1,66 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
 use itertools::Itertools;
 
-fn parse(line: &str) -> Option<u32> {
-    line.parse().ok()
-}
+pub const META: Meta = Meta {
+    day: 1,
+    title: "Report Repair",
+    input: include_str!("inputs/1"),
+};
 
-fn with_combination_size(input: &Input, n: usize) -> String {
-    let combinations = input.0.lines().filter_map(parse).combinations(n);
+/// Find `n` entries summing up to 2020 and return their product
+pub fn with_combination_size(entries: &[u32], n: usize) -> Option<u64> {
+    let combinations = entries.iter().copied().combinations(n);
 
     for c in combinations {
         // this call to 'fold' is equivalent to a sum over the elements of 'c'
         if itertools::fold(&c, 0, |a, b| a + b) == 2020 {
-            let product: u32 = c.into_iter().product();
-            return product.to_string();
+            return Some(c.into_iter().map(u64::from).product());
         }
     }
 
-    String::new()
+    None
+}
+
+fn solve(entries: &[u32], n: usize) -> Result<Answer, SolveError> {
+    let product = with_combination_size(entries, n).ok_or(SolveError::NoSolution)?;
+    Ok(product.into())
 }
 
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        with_combination_size(input, 2)
+    type Parsed<'i> = Vec<u32>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        input.parse_lines()
+    }
+
+    fn part1(&self, entries: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        solve(entries, 2)
+    }
+
+    fn part2(&self, entries: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        solve(entries, 3)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn part2(&self, input: &Input) -> String {
-        with_combination_size(input, 3)
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Day.parse(&Input("1721\n97x\n")),
+            Err(SolveError::Parse {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+        assert_eq!(
+            Day.part1(&vec![1721, 979]),
+            Err(SolveError::NoSolution)
+        );
     }
 }
@@ -1,32 +1,44 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
+
+pub const META: Meta = Meta {
+    day: 2,
+    title: "Password Philosophy",
+    input: include_str!("inputs/2"),
+};
 
 #[derive(Clone, Debug)]
-struct Parsed<'s> {
-    policy: (usize, usize),
-    character: char,
-    password: &'s str,
+pub struct Password<'s> {
+    pub policy: (usize, usize),
+    pub character: char,
+    pub password: &'s str,
 }
 
-fn parse(line: &str) -> Option<Parsed<'_>> {
-    let line: Vec<_> = line.splitn(3, ' ').collect();
+/// Parse the `n`th line (starting at 0) of the input
+pub fn parse((n, line): (usize, &str)) -> Result<Password<'_>, SolveError> {
+    let error = |column: usize, message| SolveError::parse(n + 1, column + 1, message);
+    let fields: Vec<_> = line.splitn(3, ' ').collect();
 
-    match &line[..] {
-        [policy, character, password] => Some(Parsed {
-            // If the following unwraps panic, something is wrong with the input dataset
+    match &fields[..] {
+        [policy, character, password] => Ok(Password {
             policy: {
-                let policy: Vec<_> = policy.splitn(2, '-').collect();
-                let a = policy[0].parse().unwrap();
-                let b = policy[1].parse().unwrap();
+                let (a, b) = policy
+                    .split_once('-')
+                    .ok_or_else(|| error(0, "Expected '<a>-<b>'"))?;
+                let a = a.parse().map_err(|_| error(0, "Invalid number"))?;
+                let b = b.parse().map_err(|_| error(policy.len() - b.len(), "Invalid number"))?;
                 (a, b)
             },
-            character: character.chars().next().unwrap(),
+            character: character
+                .chars()
+                .next()
+                .ok_or_else(|| error(policy.len() + 1, "Missing character"))?,
             password,
         }),
-        _ => None,
+        _ => Err(error(0, "Expected '<a>-<b> <character>: <password>'")),
     }
 }
 
-fn validate1(parsed: &Parsed<'_>) -> bool {
+pub fn validate1(parsed: &Password<'_>) -> bool {
     let count = parsed
         .password
         .chars()
@@ -36,39 +48,62 @@ fn validate1(parsed: &Parsed<'_>) -> bool {
     parsed.policy.0 <= count && count <= parsed.policy.1
 }
 
-fn validate2(parsed: &Parsed<'_>) -> bool {
+pub fn validate2(parsed: &Password<'_>) -> bool {
     let c = parsed.character;
-    if let (Some(a), Some(b)) = (
-        parsed.password.chars().nth(parsed.policy.0 - 1),
-        parsed.password.chars().nth(parsed.policy.1 - 1),
-    ) {
+    let nth = |position: usize| {
+        // positions start at 1
+        position
+            .checked_sub(1)
+            .and_then(|i| parsed.password.chars().nth(i))
+    };
+
+    if let (Some(a), Some(b)) = (nth(parsed.policy.0), nth(parsed.policy.1)) {
         return a == c && b != c || a != c && b == c;
     }
 
     false
 }
 
-fn validate_with<P>(input: &Input, predicate: P) -> String
-where
-    P: FnMut(&Parsed<'_>) -> bool,
-{
-    input
-        .0
-        .lines()
-        .filter_map(parse)
-        .filter(predicate)
-        .count()
-        .to_string()
-}
-
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        validate_with(input, validate1)
+    type Parsed<'i> = Vec<Password<'i>>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(parse)
+            .collect()
     }
 
-    fn part2(&self, input: &Input) -> String {
-        validate_with(input, validate2)
+    fn part1(&self, passwords: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(passwords.iter().filter(|p| validate1(p)).count().into())
+    }
+
+    fn part2(&self, passwords: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(passwords.iter().filter(|p| validate2(p)).count().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse((1, "1-x a: abcde")).map(|_| ()),
+            Err(SolveError::parse(2, 3, "Invalid number"))
+        );
+        assert_eq!(
+            parse((0, "1-3 a:abcde")).map(|_| ()),
+            Err(SolveError::parse(
+                1,
+                1,
+                "Expected '<a>-<b> <character>: <password>'"
+            ))
+        );
     }
 }
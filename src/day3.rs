@@ -1,54 +1,39 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Grid, Input, Meta, SolveError};
 use std::convert::{TryFrom, TryInto};
 
+pub const META: Meta = Meta {
+    day: 3,
+    title: "Toboggan Trajectory",
+    input: include_str!("inputs/3"),
+};
+
 #[derive(Clone, Debug)]
-struct Forest {
-    data: String,
-    width: usize,
-    height: usize,
-}
+pub struct Forest(Grid);
 
 impl TryFrom<&Input<'_>> for Forest {
-    type Error = &'static str;
+    type Error = SolveError;
 
     fn try_from(input: &Input) -> Result<Self, Self::Error> {
-        let data = input.0.to_string();
-        let width = input.0.find('\n').ok_or("Can't find \\n in input")?;
-        let height = input
-            .0
-            .as_bytes()
-            .iter()
-            .filter(|c| **c as char == '\n') // search for newlines
-            .count();
-
-        Ok(Forest {
-            data: data.replace('\n', ""), // remove newlines
-            width,
-            height,
-        })
+        input.grid().map(Forest)
     }
 }
 
 impl Forest {
-    fn traverse(&self, slope: (usize, usize)) -> impl Iterator<Item = usize> + '_ {
+    pub fn traverse(&self, slope: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
         (0..)
-            .map(move |i| {
-                // generate all possible coordinates for the given slope
-                // columns wrap around
-                let coord = ((i * slope.0) % self.width, i * slope.1);
-                // calculate the array index of the coordinate
-                coord.0 + coord.1 * self.width
-            })
-            .take_while(move |pos| *pos < self.height * self.width)
+            // generate all possible coordinates for the given slope
+            // columns wrap around
+            .map(move |i| ((i * slope.0) % self.0.width, i * slope.1))
+            .take_while(move |coord| coord.1 < self.0.height)
     }
 
-    fn get(&self, index: usize) -> char {
-        self.data.as_bytes()[index] as char
+    pub fn get(&self, (x, y): (usize, usize)) -> char {
+        self.0.get(x, y).map_or('.', char::from)
     }
 
-    fn count_with_slope(&self, slope: (usize, usize)) -> usize {
+    pub fn count_with_slope(&self, slope: (usize, usize)) -> usize {
         self.traverse(slope)
-            .filter(|pos| self.get(*pos) == '#')
+            .filter(|coord| self.get(*coord) == '#')
             .count()
     }
 }
@@ -56,15 +41,40 @@ impl Forest {
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        let forest: Forest = input.try_into().unwrap();
-        forest.count_with_slope((3, 1)).to_string()
+    type Parsed<'i> = Forest;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        input.try_into()
+    }
+
+    fn part1(&self, forest: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(forest.count_with_slope((3, 1)).into())
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let forest: Forest = input.try_into().unwrap();
+    fn part2(&self, forest: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
         let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
         let product: usize = slopes.iter().map(|s| forest.count_with_slope(*s)).product();
-        product.to_string()
+        Ok(product.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Forest::try_from(&Input("..#\n#.\n")).map(|_| ()),
+            Err(SolveError::parse(
+                2,
+                1,
+                "Expected 3 characters like the first line"
+            ))
+        );
+        assert_eq!(
+            Forest::try_from(&Input("")).map(|_| ()),
+            Err(SolveError::invalid("The grid is empty"))
+        );
     }
 }
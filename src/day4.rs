@@ -1,23 +1,44 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+pub const META: Meta = Meta {
+    day: 4,
+    title: "Passport Processing",
+    input: include_str!("inputs/4"),
+};
 
 const MAX_ENTRIES: usize = 8;
 
-fn parse<'a>(input: &'a Input) -> impl Iterator<Item = HashMap<&'a str, &'a str>> {
-    input.0.split("\n\n").map(|record| {
-        let mut set = HashMap::with_capacity(MAX_ENTRIES);
-        for entry in record.split_ascii_whitespace() {
-            let mut components = entry.split(':');
-            let kind = components.next().unwrap(); // if unwrap panics, input is invalid
-            let value = components.next().unwrap();
-            set.insert(kind, value);
-        }
+pub fn parse<'a>(input: &Input<'a>) -> Result<Vec<HashMap<&'a str, &'a str>>, SolveError> {
+    // records are separated by empty lines
+    input
+        .blocks()
+        .into_iter()
+        .map(|block| {
+            let mut record = HashMap::with_capacity(MAX_ENTRIES);
+
+            for (n, line) in (block.line..).zip(block.lines) {
+                // entries are separated by any whitespace, every piece ends with one separator
+                let mut column = 0;
+                for piece in line.split_inclusive(char::is_whitespace) {
+                    let entry = piece.trim_end();
+                    if !entry.is_empty() {
+                        let (kind, value) = entry.split_once(':').ok_or_else(|| {
+                            SolveError::parse(n, column + 1, "Expected '<field>:<value>'")
+                        })?;
+                        record.insert(kind, value);
+                    }
+                    column += piece.len();
+                }
+            }
 
-        set
-    })
+            Ok(record)
+        })
+        .collect()
 }
 
-fn is_valid1(record: &HashMap<&str, &str>) -> bool {
+pub fn is_valid1(record: &HashMap<&str, &str>) -> bool {
     if record.len() == MAX_ENTRIES {
         true
     } else {
@@ -25,42 +46,39 @@ fn is_valid1(record: &HashMap<&str, &str>) -> bool {
     }
 }
 
-fn is_valid2(record: &HashMap<&str, &str>) -> bool {
-    let byr = record.get("byr").unwrap().parse::<i32>().unwrap();
-    if byr < 1920 || byr > 2002 {
-        return false;
-    }
+pub fn is_valid2(record: &HashMap<&str, &str>) -> bool {
+    let number_in = |value: &str, range: RangeInclusive<i32>| {
+        value
+            .parse::<i32>()
+            .is_ok_and(|value| range.contains(&value))
+    };
+    let field = |kind| record.get(kind).copied().unwrap_or_default();
 
-    let iyr = record.get("iyr").unwrap().parse::<i32>().unwrap();
-    if iyr < 2010 || iyr > 2020 {
-        return false;
-    }
-
-    let eyr = record.get("eyr").unwrap().parse::<i32>().unwrap();
-    if eyr < 2020 || eyr > 2030 {
+    if !number_in(field("byr"), 1920..=2002)
+        || !number_in(field("iyr"), 2010..=2020)
+        || !number_in(field("eyr"), 2020..=2030)
+    {
         return false;
     }
 
-    let hgt = record.get("hgt").unwrap();
+    let hgt = field("hgt");
     if let Some(hgt) = hgt.strip_suffix("cm") {
-        let hgt: i32 = hgt.parse().unwrap();
-        if hgt < 150 || hgt > 193 {
+        if !number_in(hgt, 150..=193) {
             return false;
         }
     } else if let Some(hgt) = hgt.strip_suffix("in") {
-        let hgt: i32 = hgt.parse().unwrap();
-        if hgt < 59 || hgt > 76 {
+        if !number_in(hgt, 59..=76) {
             return false;
         }
     } else {
         return false;
     }
 
-    let hcl = record.get("hcl").unwrap();
+    let hcl = field("hcl");
     if let Some(hcl) = hcl.strip_prefix("#") {
         if hcl
             .chars()
-            .filter(|c| (*c >= '0' && *c <= '9') || (*c >= 'a' && *c <= 'f'))
+            .filter(|c| c.is_ascii_digit() || ('a'..='f').contains(c))
             .count()
             != 6
         {
@@ -70,17 +88,17 @@ fn is_valid2(record: &HashMap<&str, &str>) -> bool {
         return false;
     }
 
-    let ecl = record.get("ecl").unwrap();
+    let ecl = field("ecl");
     if ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]
         .iter()
-        .filter(|color| *color == ecl)
+        .filter(|color| **color == ecl)
         .count()
         == 0
     {
         return false;
     }
 
-    let pid = record.get("pid").unwrap();
+    let pid = field("pid");
     if pid.len() != 9 || pid.parse::<i32>().is_err() {
         return false;
     }
@@ -91,15 +109,39 @@ fn is_valid2(record: &HashMap<&str, &str>) -> bool {
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        let count: usize = parse(input).filter(is_valid1).count();
-        count.to_string()
+    type Parsed<'i> = Vec<HashMap<&'i str, &'i str>>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        parse(input)
+    }
+
+    fn part1(&self, records: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let count: usize = records.iter().filter(|r| is_valid1(r)).count();
+        Ok(count.into())
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let count: usize = parse(input)
+    fn part2(&self, records: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let count: usize = records
+            .iter()
             .filter(|r| is_valid1(r) && is_valid2(r))
             .count();
-        count.to_string()
+        Ok(count.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separators() {
+        let records = parse(&Input("ecl:gry\tpid:1  eyr:2\n\nhcl:#fff\n")).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("eyr"), Some(&"2"));
+
+        assert_eq!(
+            parse(&Input("ecl:gry\n\nhcl:#fff \tpid\n")),
+            Err(SolveError::parse(3, 11, "Expected '<field>:<value>'"))
+        );
     }
 }
@@ -1,27 +1,47 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
 use std::str::FromStr;
 
+pub const META: Meta = Meta {
+    day: 5,
+    title: "Binary Boarding",
+    input: include_str!("inputs/5"),
+};
+
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Seat {
-    row: usize,
-    col: usize,
-    id: usize,
+pub struct Seat {
+    pub row: usize,
+    pub col: usize,
+    pub id: usize,
 }
 
-fn parse<'a>(input: &Input<'a>) -> impl Iterator<Item = &'a str> {
-    input.0.split_ascii_whitespace()
+fn parse(input: &Input) -> Result<Vec<usize>, SolveError> {
+    Ok(input
+        .parse_lines::<Seat>()?
+        .into_iter()
+        .map(|s| s.id)
+        .collect())
 }
 
 impl FromStr for Seat {
-    type Err = ();
+    type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let b = s.as_bytes();
 
+        if b.len() != 10 {
+            return Err("Expected 10 characters");
+        }
+        if !b[..7].iter().all(|c| *c == b'F' || *c == b'B') {
+            return Err("Expected F or B in the first 7 characters");
+        }
+        if !b[7..].iter().all(|c| *c == b'L' || *c == b'R') {
+            return Err("Expected L or R in the last 3 characters");
+        }
+
         let rows = &b[0..8];
         let cols = &b[7..10];
 
-        let row = rows.iter().fold((0, 127), |rows, p| {
+        let row = rows.iter().fold((0usize, 127), |rows, p| {
             if *p as char == 'F' {
                 (rows.0, (rows.0 + rows.1) / 2)
             } else {
@@ -29,7 +49,7 @@ impl FromStr for Seat {
             }
         });
 
-        let col = cols.iter().fold((0, 7), |cols, p| {
+        let col = cols.iter().fold((0usize, 7), |cols, p| {
             if *p as char == 'L' {
                 (cols.0, (cols.0 + cols.1) / 2)
             } else {
@@ -48,34 +68,32 @@ impl FromStr for Seat {
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        let max = parse(input)
-            .filter_map(|i| i.parse::<Seat>().ok())
-            .map(|s| s.id)
-            .max()
-            .unwrap();
-
-        max.to_string()
+    type Parsed<'i> = Vec<usize>;
+
+    /// Parse the seat ids, sorted in ascending order
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        let mut seats = parse(input)?;
+        seats.sort_unstable();
+        Ok(seats)
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let mut seats: Vec<_> = parse(input)
-            .filter_map(|i| i.parse::<Seat>().ok())
-            .map(|s| s.id)
-            .collect();
+    fn part1(&self, seats: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let max = seats.last().ok_or(SolveError::NoSolution)?;
 
-        seats.sort_unstable();
+        Ok((*max).into())
+    }
 
+    fn part2(&self, seats: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
         for seats in seats.windows(2) {
             let a = seats[0];
             let b = seats[1];
 
             if a + 1 != b {
-                return (a + 1).to_string();
+                return Ok((a + 1).into());
             }
         }
 
-        String::new()
+        Err(SolveError::NoSolution)
     }
 }
 
@@ -117,5 +135,9 @@ mod tests {
                 id: 820
             }
         );
+
+        assert!("BFFFBBFRR".parse::<Seat>().is_err());
+        assert!("BFFFBBFRRL".parse::<Seat>().is_ok());
+        assert!("BFFFBBRRRL".parse::<Seat>().is_err());
     }
 }
@@ -1,12 +1,18 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
+
+pub const META: Meta = Meta {
+    day: 6,
+    title: "Custom Customs",
+    input: include_str!("inputs/6"),
+};
 
 pub struct Day;
 
-fn count_anyone(s: &str) -> usize {
+pub fn count_anyone(group: &[&str]) -> usize {
     let mut counter: usize = 0;
 
-    for c in s.bytes() {
-        if c >= b'a' && c <= b'z' {
+    for c in group.iter().flat_map(|line| line.bytes()) {
+        if c.is_ascii_lowercase() {
             let pos = c - b'a';
             counter |= 1 << pos;
         }
@@ -15,33 +21,50 @@ fn count_anyone(s: &str) -> usize {
     counter.count_ones() as usize
 }
 
-fn count_everyone(s: &str) -> usize {
+pub fn count_everyone(group: &[&str]) -> usize {
     let mut counter = [0usize; 26];
 
-    for line in s.lines() {
+    for line in group {
         for c in line.bytes() {
-            counter[(c - b'a') as usize] += 1;
+            if c.is_ascii_lowercase() {
+                counter[(c - b'a') as usize] += 1;
+            }
         }
     }
 
-    let n_lines = s.lines().count();
-
-    counter.iter().filter(|count| **count == n_lines).count()
+    counter.iter().filter(|count| **count == group.len()).count()
 }
 
-fn groups<'a>(input: &Input<'a>) -> impl Iterator<Item = &'a str> {
-    input.0.split("\n\n")
+fn groups<'a>(input: &Input<'a>) -> Result<Vec<Vec<&'a str>>, SolveError> {
+    for (n, line) in input.lines().enumerate() {
+        if let Some(column) = line.bytes().position(|c| !c.is_ascii_lowercase()) {
+            return Err(SolveError::parse(
+                n + 1,
+                column + 1,
+                "Expected answers from a to z",
+            ));
+        }
+    }
+
+    // groups are separated by empty lines
+    Ok(input.blocks().into_iter().map(|block| block.lines).collect())
 }
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        let anyones: usize = groups(input).map(count_anyone).sum();
-        anyones.to_string()
+    type Parsed<'i> = Vec<Vec<&'i str>>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        groups(input)
+    }
+
+    fn part1(&self, groups: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let anyones: usize = groups.iter().map(|group| count_anyone(group)).sum();
+        Ok(anyones.into())
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let everyones: usize = groups(input).map(count_everyone).sum();
-        everyones.to_string()
+    fn part2(&self, groups: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let everyones: usize = groups.iter().map(|group| count_everyone(group)).sum();
+        Ok(everyones.into())
     }
 }
 
@@ -51,19 +74,20 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(count_anyone("abc"), 3);
-        assert_eq!(count_anyone("a\nb\nc"), 3);
-        assert_eq!(count_anyone("ab\nac"), 3);
-        assert_eq!(count_anyone("a\na\na\na"), 1);
-        assert_eq!(count_anyone("b"), 1);
+        assert_eq!(count_anyone(&["abc"]), 3);
+        assert_eq!(count_anyone(&["a", "b", "c"]), 3);
+        assert_eq!(count_anyone(&["ab", "ac"]), 3);
+        assert_eq!(count_anyone(&["a", "a", "a", "a"]), 1);
+        assert_eq!(count_anyone(&["b"]), 1);
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(count_everyone("abc"), 3);
-        assert_eq!(count_everyone("a\nb\nc"), 0);
-        assert_eq!(count_everyone("ab\nac"), 1);
-        assert_eq!(count_everyone("a\na\na\na"), 1);
-        assert_eq!(count_everyone("b"), 1);
+        assert_eq!(count_everyone(&["abc"]), 3);
+        assert_eq!(count_everyone(&["a", "b", "c"]), 0);
+        assert_eq!(count_everyone(&["ab", "ac"]), 1);
+        assert_eq!(count_everyone(&["a", "a", "a", "a"]), 1);
+        assert_eq!(count_everyone(&["b"]), 1);
+        assert_eq!(count_everyone(&["aB", "a-"]), 1);
     }
 }
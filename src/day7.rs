@@ -1,18 +1,24 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
 use regex::Regex;
 use std::collections::HashMap;
 
-const REGEX_CONTAINER: &str = r"^([[:alpha:][:space:]]+?) bags contain";
-const REGEX_CONTAINEES: &str = r",? ([[:digit:]]+?) ([[:alpha:][:space:]]+?) bags?";
+pub const META: Meta = Meta {
+    day: 7,
+    title: "Handy Haversacks",
+    input: include_str!("inputs/7"),
+};
+
+pub const REGEX_CONTAINER: &str = r"^([[:alpha:][:space:]]+?) bags contain";
+pub const REGEX_CONTAINEES: &str = r",? ([[:digit:]]+?) ([[:alpha:][:space:]]+?) bags?";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Rule<'a> {
-    color: &'a str,
-    others: HashMap<&'a str, usize>,
+pub struct Rule<'a> {
+    pub color: &'a str,
+    pub others: HashMap<&'a str, usize>,
 }
 
 impl<'a> Rule<'a> {
-    fn try_from_str(rule: &'a str, regexes: &(Regex, Regex)) -> Result<Self, &'static str> {
+    pub fn try_from_str(rule: &'a str, regexes: &(Regex, Regex)) -> Result<Self, &'static str> {
         let color = if let Some(container) = regexes
             .0 // regex for the container color
             .captures_iter(rule)
@@ -40,26 +46,23 @@ impl<'a> Rule<'a> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct ReducedRule<'a> {
-    color: &'a str,
-    count: usize,
-}
+const CYCLIC_RULES: &str = "The rules are cyclic or refer to bags without rules";
 
-fn reduce<'a, I: Iterator<Item = Rule<'a>>>(rules: I, color: &str) -> usize {
+/// Count the bags which (transitively) contain a bag of `color`
+pub fn reduce<'a, I: Iterator<Item = Rule<'a>>>(rules: I, color: &str) -> Result<usize, SolveError> {
     let mut bags: Vec<_> = rules
-        .filter_map(|mut bag| {
+        .map(|mut bag| {
             if let Some(count) = bag.others.remove(color) {
-                Some((bag, count))
+                (bag, count)
             } else {
-                Some((bag, 0))
+                (bag, 0)
             }
         })
         .collect();
 
     let mut reduced: HashMap<&str, usize> = HashMap::new();
 
-    while bags.len() > 0 {
+    while !bags.is_empty() {
         for (Rule { color: _, others }, count) in bags.iter_mut() {
             for (red_color, red_count) in reduced.iter() {
                 if let Some(c) = others.remove(red_color) {
@@ -68,25 +71,35 @@ fn reduce<'a, I: Iterator<Item = Rule<'a>>>(rules: I, color: &str) -> usize {
             }
         }
 
+        let remaining = bags.len();
+
         bags.retain(|(Rule { color, others }, count)| {
-            if others.len() == 0 {
+            if others.is_empty() {
                 reduced.insert(color, *count);
                 false
             } else {
                 true
             }
         });
+
+        if bags.len() == remaining {
+            return Err(SolveError::invalid(CYCLIC_RULES));
+        }
     }
 
-    reduced.iter().filter(|(_color, count)| **count > 0).count()
+    Ok(reduced.iter().filter(|(_color, count)| **count > 0).count())
 }
 
-fn count_bags<'a, I: Iterator<Item = Rule<'a>>>(rules: I, color: &str) -> usize {
-    let mut bags: Vec<_> = rules.filter_map(|bag| Some((bag, 1))).collect();
+/// Count the bags inside a bag of `color`
+pub fn count_bags<'a, I: Iterator<Item = Rule<'a>>>(
+    rules: I,
+    color: &str,
+) -> Result<usize, SolveError> {
+    let mut bags: Vec<_> = rules.map(|bag| (bag, 1)).collect();
 
     let mut reduced: HashMap<&str, usize> = HashMap::new();
 
-    while bags.len() > 0 {
+    while !bags.is_empty() {
         for (Rule { color: _, others }, count) in bags.iter_mut() {
             for (red_color, red_count) in reduced.iter() {
                 if let Some(c) = others.remove(red_color) {
@@ -95,48 +108,59 @@ fn count_bags<'a, I: Iterator<Item = Rule<'a>>>(rules: I, color: &str) -> usize
             }
         }
 
+        let remaining = bags.len();
+
         bags.retain(|(Rule { color, others }, count)| {
-            if others.len() == 0 {
+            if others.is_empty() {
                 reduced.insert(color, *count);
                 false
             } else {
                 true
             }
         });
+
+        if bags.len() == remaining {
+            return Err(SolveError::invalid(CYCLIC_RULES));
+        }
     }
 
-    reduced.get(color).unwrap() - 1
+    reduced
+        .get(color)
+        .map(|count| count - 1)
+        .ok_or_else(|| SolveError::invalid(format!("There is no rule for {} bags", color)))
+}
+
+fn parse<'a>(input: &Input<'a>, regexes: &(Regex, Regex)) -> Result<Vec<Rule<'a>>, SolveError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, rule)| !rule.is_empty())
+        .map(|(n, rule)| {
+            Rule::try_from_str(rule, regexes).map_err(|e| SolveError::parse(n + 1, 1, e))
+        })
+        .collect()
 }
 
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
+    type Parsed<'i> = Vec<Rule<'i>>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
         let re = (
             Regex::new(REGEX_CONTAINER).unwrap(),
             Regex::new(REGEX_CONTAINEES).unwrap(),
         );
 
-        let parsed_rules = input
-            .0
-            .lines()
-            .filter_map(|rule| Rule::try_from_str(rule, &re).ok());
-
-        reduce(parsed_rules, "shiny gold").to_string()
+        parse(input, &re)
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let re = (
-            Regex::new(REGEX_CONTAINER).unwrap(),
-            Regex::new(REGEX_CONTAINEES).unwrap(),
-        );
-
-        let parsed_rules = input
-            .0
-            .lines()
-            .filter_map(|rule| Rule::try_from_str(rule, &re).ok());
+    fn part1(&self, rules: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(reduce(rules.iter().cloned(), "shiny gold")?.into())
+    }
 
-        count_bags(parsed_rules, "shiny gold").to_string()
+    fn part2(&self, rules: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(count_bags(rules.iter().cloned(), "shiny gold")?.into())
     }
 }
 
@@ -167,7 +191,7 @@ mod tests {
             .iter()
             .filter_map(|rule| Rule::try_from_str(rule, &re).ok());
 
-        assert_eq!(reduce(parsed_rules, "shiny gold"), 4);
+        assert_eq!(reduce(parsed_rules, "shiny gold"), Ok(4));
     }
 
     #[test]
@@ -181,7 +205,7 @@ mod tests {
             .iter()
             .filter_map(|rule| Rule::try_from_str(rule, &re).ok());
 
-        assert_eq!(count_bags(parsed_rules, "shiny gold"), 32);
+        assert_eq!(count_bags(parsed_rules, "shiny gold"), Ok(32));
     }
 
     #[test]
@@ -248,5 +272,24 @@ mod tests {
     }
 
     #[test]
-    fn samples_part2() {}
+    fn cyclic_rules() {
+        let re = (
+            Regex::new(REGEX_CONTAINER).unwrap(),
+            Regex::new(REGEX_CONTAINEES).unwrap(),
+        );
+
+        let rules = [
+            "light red bags contain 1 dark orange bag.",
+            "dark orange bags contain 2 light red bags.",
+            "shiny gold bags contain no other bags.",
+        ];
+        let parsed_rules = || {
+            rules
+                .iter()
+                .filter_map(|rule| Rule::try_from_str(rule, &re).ok())
+        };
+
+        assert!(reduce(parsed_rules(), "shiny gold").is_err());
+        assert!(count_bags(parsed_rules(), "shiny gold").is_err());
+    }
 }
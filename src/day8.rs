@@ -1,9 +1,21 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
 use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
 use std::str::FromStr;
 
+pub mod cfg;
+pub mod debugger;
+pub mod trace;
+
+pub const META: Meta = Meta {
+    day: 8,
+    title: "Handheld Halting",
+    input: include_str!("inputs/8"),
+};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Instruction {
+pub enum Instruction {
     Nop(isize),
     Acc(i32),
     Jmp(isize),
@@ -33,8 +45,56 @@ impl FromStr for Instruction {
     }
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nop(x) | Self::Jmp(x) => write!(f, "{} {:+}", self.kind(), x),
+            Self::Acc(x) => write!(f, "{} {:+}", self.kind(), x),
+        }
+    }
+}
+
+/// The operation of an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    Nop,
+    Acc,
+    Jmp,
+}
+
+impl FromStr for Kind {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nop" => Ok(Self::Nop),
+            "acc" => Ok(Self::Acc),
+            "jmp" => Ok(Self::Jmp),
+            _ => Err("Invalid instruction"),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Nop => "nop",
+            Self::Acc => "acc",
+            Self::Jmp => "jmp",
+        })
+    }
+}
+
 impl Instruction {
-    fn switch_nop_jmp(&mut self) {
+    pub fn kind(&self) -> Kind {
+        match self {
+            Self::Nop(_) => Kind::Nop,
+            Self::Acc(_) => Kind::Acc,
+            Self::Jmp(_) => Kind::Jmp,
+        }
+    }
+
+    pub fn switch_nop_jmp(&mut self) {
         let _ = std::mem::replace(
             self,
             match self {
@@ -45,7 +105,7 @@ impl Instruction {
         );
     }
 
-    fn terminates_with_accu(program: &[Instruction]) -> Option<i32> {
+    pub fn terminates_with_accu(program: &[Instruction]) -> Option<i32> {
         let mut instruction_counter = HashSet::new();
         let mut accu = None;
 
@@ -63,22 +123,26 @@ impl Instruction {
         accu
     }
 
-    fn terminates_with_switched_instructions(program: &mut [Instruction]) -> i32 {
-        let mut last_instruction = None;
+    /// Switch single nop and jmp instructions until the program terminates,
+    /// `None` if no single switch makes the program terminate
+    pub fn terminates_with_switched_instructions(program: &mut [Instruction]) -> Option<i32> {
+        let mut last_instruction: Option<usize> = None;
 
         loop {
             // revert the last change to the program
             let last = if let Some(last) = last_instruction {
-                program[last as usize].switch_nop_jmp();
+                program[last].switch_nop_jmp();
                 last + 1
             } else {
                 0
             };
 
-            for i in last..program.len() {
-                match program[i] {
+            last_instruction = None;
+
+            for (i, instruction) in program.iter_mut().enumerate().skip(last) {
+                match instruction {
                     Instruction::Nop(_) | Instruction::Jmp(_) => {
-                        program[i].switch_nop_jmp();
+                        instruction.switch_nop_jmp();
                         last_instruction = Some(i);
                         break;
                     }
@@ -86,9 +150,12 @@ impl Instruction {
                 }
             }
 
+            // every instruction has been switched without success
+            last_instruction?;
+
             let mut instruction_counter = HashSet::new();
 
-            let mut process = Process::new(&program);
+            let mut process = Process::new(program);
             process.run_and(|p| {
                 if instruction_counter.contains(&p.pc) {
                     false
@@ -98,38 +165,113 @@ impl Instruction {
                 }
             });
 
-            if process.pc as usize >= program.len() {
-                break process.acc;
+            if process.terminated() {
+                break Some(process.acc);
+            }
+        }
+    }
+
+    /// The pc after executing this instruction at `pc`
+    pub fn next_pc(&self, pc: usize) -> isize {
+        match self {
+            Self::Jmp(offset) => pc as isize + offset,
+            Self::Nop(_) | Self::Acc(_) => pc as isize + 1,
+        }
+    }
+
+    /// The index of the nop or jmp instruction to switch to make the program terminate,
+    /// `None` if the program already terminates or no single switch makes it terminate.
+    ///
+    /// Runs in linear time: the instructions which lead to the end of the program are
+    /// found walking the jumps backwards from the end, then the program is run once
+    /// until a switched instruction would lead to one of them.
+    pub fn repair(program: &[Instruction]) -> Option<usize> {
+        let end = program.len() as isize;
+        let index = |pc: isize| usize::try_from(pc).ok().filter(|&pc| pc < program.len());
+
+        // the instructions leading to every instruction
+        let mut predecessors = vec![Vec::new(); program.len()];
+        let mut terminating = vec![false; program.len()];
+        let mut pending = Vec::new();
+        for (pc, instruction) in program.iter().enumerate() {
+            let next = instruction.next_pc(pc);
+            if next == end {
+                terminating[pc] = true;
+                pending.push(pc);
+            } else if let Some(next) = index(next) {
+                predecessors[next].push(pc);
+            }
+        }
+
+        while let Some(pc) = pending.pop() {
+            for &predecessor in &predecessors[pc] {
+                if !terminating[predecessor] {
+                    terminating[predecessor] = true;
+                    pending.push(predecessor);
+                }
             }
         }
+
+        // jumping out of the program doesn't terminate it
+        let terminates = |pc: isize| pc == end || index(pc).is_some_and(|pc| terminating[pc]);
+        if terminates(0) {
+            return None;
+        }
+
+        let mut pc = index(0);
+        let mut executed = vec![false; program.len()];
+        while let Some(current) = pc {
+            if executed[current] {
+                return None;
+            }
+            executed[current] = true;
+
+            let mut switched = program[current];
+            switched.switch_nop_jmp();
+            if switched != program[current] && terminates(switched.next_pc(current)) {
+                return Some(current);
+            }
+
+            pc = index(program[current].next_pc(current));
+        }
+
+        None
+    }
+
+    /// Like `terminates_with_switched_instructions`, but finds the instruction to
+    /// switch with `repair` in linear time
+    pub fn terminates_with_repaired_instruction(program: &[Instruction]) -> Option<i32> {
+        let switch = Self::repair(program)?;
+        let mut program = program.to_vec();
+        program[switch].switch_nop_jmp();
+
+        let mut process = Process::new(&program);
+        while process.step().is_running() {}
+        Some(process.acc)
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum State {
+pub enum State {
     Running,
     Stopped,
 }
 
 impl State {
-    fn is_running(&self) -> bool {
-        if let Self::Running = self {
-            true
-        } else {
-            false
-        }
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running)
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Process<'a> {
-    program: &'a [Instruction],
-    pc: isize,
-    acc: i32,
+pub struct Process<'a> {
+    pub program: &'a [Instruction],
+    pub pc: isize,
+    pub acc: i32,
 }
 
 impl<'a> Process<'a> {
-    fn new(program: &'a [Instruction]) -> Self {
+    pub fn new(program: &'a [Instruction]) -> Self {
         Self {
             program,
             pc: 0,
@@ -137,8 +279,21 @@ impl<'a> Process<'a> {
         }
     }
 
-    fn step(&mut self) -> State {
-        if let Some(instruction) = self.program.get(self.pc as usize) {
+    /// The pc as an index into the program, `None` if it's outside of the program
+    pub fn index(&self) -> Option<usize> {
+        usize::try_from(self.pc)
+            .ok()
+            .filter(|&pc| pc < self.program.len())
+    }
+
+    /// Whether the program terminated by running past its last instruction,
+    /// leaving the program in any other way is not a termination
+    pub fn terminated(&self) -> bool {
+        self.pc == self.program.len() as isize
+    }
+
+    pub fn step(&mut self) -> State {
+        if let Some(instruction) = self.index().map(|pc| &self.program[pc]) {
             match instruction {
                 Instruction::Nop(_) => {
                     self.pc += 1;
@@ -160,7 +315,7 @@ impl<'a> Process<'a> {
     /// Run the program until the program counter is invalid.  
     /// f is called *before* every execution cycle.  
     /// If f returns false, the execution stops
-    fn run_and<F>(&mut self, mut f: F)
+    pub fn run_and<F>(&mut self, mut f: F)
     where
         F: FnMut(&mut Process) -> bool,
     {
@@ -179,26 +334,22 @@ impl<'a> Process<'a> {
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        let program: Vec<Instruction> = input
-            .0
-            .lines()
-            .filter_map(|line| line.parse().ok())
-            .collect();
+    type Parsed<'i> = Vec<Instruction>;
 
-        Instruction::terminates_with_accu(&program)
-            .unwrap()
-            .to_string()
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        input.parse_lines()
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let mut program: Vec<Instruction> = input
-            .0
-            .lines()
-            .filter_map(|line| line.parse().ok())
-            .collect();
+    fn part1(&self, program: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Instruction::terminates_with_accu(program)
+            .map(Answer::from)
+            .ok_or(SolveError::NoSolution)
+    }
 
-        Instruction::terminates_with_switched_instructions(&mut program).to_string()
+    fn part2(&self, program: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Instruction::terminates_with_repaired_instruction(program)
+            .map(Answer::from)
+            .ok_or(SolveError::NoSolution)
     }
 }
 
@@ -206,50 +357,90 @@ impl DayTrait for Day {
 mod tests {
     use super::*;
 
-    const SAMPLE1: &str = "nop +0
-                           acc +1
-                           jmp +4
-                           acc +3
-                           jmp -3
-                           acc -99
-                           acc +1
-                           jmp -4
-                           acc +6
-                           ";
-
-    const SAMPLE2: &str = "nop +0
-                           acc +1
-                           jmp +4
-                           acc +3
-                           jmp -3
-                           acc -99
-                           acc +1
-                           jmp -4
-                           acc +6
-                           ";
-
-    #[test]
-    fn example_part1() {
-        let program: Vec<Instruction> = SAMPLE1
+    /// The example from the puzzle description, also in `src/examples/8`
+    const SAMPLE: &str = "nop +0
+                          acc +1
+                          jmp +4
+                          acc +3
+                          jmp -3
+                          acc -99
+                          acc +1
+                          jmp -4
+                          acc +6
+                          ";
+
+    pub(super) fn sample() -> Vec<Instruction> {
+        SAMPLE
             .lines()
             .map(str::trim_start)
             .filter_map(|line| line.parse().ok())
+            .collect()
+    }
+
+    #[test]
+    fn disassemble() {
+        let listing: Vec<_> = sample().iter().map(Instruction::to_string).collect();
+        let original: Vec<_> = SAMPLE
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
             .collect();
+        assert_eq!(listing, original);
+    }
 
-        assert_eq!(Instruction::terminates_with_accu(&program), Some(5));
+    #[test]
+    fn example_part1() {
+        assert_eq!(Instruction::terminates_with_accu(&sample()), Some(5));
     }
 
     #[test]
     fn example_part2() {
-        let mut program: Vec<Instruction> = SAMPLE2
-            .lines()
-            .map(str::trim_start)
-            .filter_map(|line| line.parse().ok())
-            .collect();
+        let mut program = sample();
 
         assert_eq!(
             Instruction::terminates_with_switched_instructions(&mut program),
-            8
+            Some(8)
+        );
+        assert_eq!(Instruction::repair(&sample()), Some(7));
+        assert_eq!(
+            Instruction::terminates_with_repaired_instruction(&sample()),
+            Some(8)
+        );
+    }
+
+    #[test]
+    fn repair() {
+        let input = Day.parse(&Input(META.input)).unwrap();
+        let mut switched = input.clone();
+        assert_eq!(
+            Instruction::terminates_with_repaired_instruction(&input),
+            Instruction::terminates_with_switched_instructions(&mut switched)
+        );
+
+        // a program which terminates already or can't be repaired
+        let terminating = vec![Instruction::Acc(1), Instruction::Jmp(1)];
+        assert_eq!(Instruction::repair(&terminating), None);
+        let looping = vec![
+            Instruction::Acc(1),
+            Instruction::Jmp(-1),
+            Instruction::Jmp(-1),
+        ];
+        assert_eq!(Instruction::repair(&looping), None);
+        assert_eq!(
+            Instruction::terminates_with_switched_instructions(&mut looping.clone()),
+            None
+        );
+
+        // jumping out of the program doesn't terminate it
+        let backwards = vec![
+            Instruction::Nop(-1),
+            Instruction::Jmp(-1),
+            Instruction::Jmp(0),
+        ];
+        assert_eq!(Instruction::repair(&backwards), None);
+        assert_eq!(
+            Instruction::terminates_with_switched_instructions(&mut backwards.clone()),
+            None
         );
     }
 }
@@ -0,0 +1,380 @@
+//! Static analysis of the boot code.
+//!
+//! The control-flow graph splits a program into basic blocks: runs of instructions
+//! which are entered at the first one and left after the last one. The boot code has
+//! no conditional jumps, so every block has exactly one successor and a program either
+//! terminates, jumps out of bounds or ends up in a loop it never leaves.
+
+use super::Instruction;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Where the execution continues after a block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// The block with the given index
+    Block(usize),
+    /// The instruction right after the last one, the program terminates
+    End,
+    /// A pc outside of the program which isn't the end
+    OutOfBounds(isize),
+}
+
+/// How a block is left
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    Jump,
+    Fallthrough,
+}
+
+/// The instructions `start..end` of a program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub target: Target,
+    pub flow: Flow,
+}
+
+/// A problem found in a program without running it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Lint {
+    /// The instructions `start..end` are never executed
+    Unreachable { start: usize, end: usize },
+    /// The program never terminates, it loops through the blocks starting at these pcs
+    InfiniteLoop { pcs: Vec<usize> },
+    /// The jmp at `pc` jumps to `target` outside of the program
+    OutOfBounds { pc: usize, target: isize },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable { start, end } if end - start == 1 => {
+                write!(f, "pc {}: unreachable instruction", start)
+            }
+            Self::Unreachable { start, end } => {
+                write!(f, "pc {}-{}: unreachable instructions", start, end - 1)
+            }
+            Self::InfiniteLoop { pcs } => {
+                let pcs: Vec<_> = pcs.iter().map(usize::to_string).collect();
+                write!(
+                    f,
+                    "pc {}: infinite loop through {}",
+                    pcs[0],
+                    pcs.join(" -> ")
+                )
+            }
+            Self::OutOfBounds { pc, target } => {
+                write!(f, "pc {}: jump out of the program to {}", pc, target)
+            }
+        }
+    }
+}
+
+/// The control-flow graph of a program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cfg<'a> {
+    program: &'a [Instruction],
+    blocks: Vec<Block>,
+}
+
+impl<'a> Cfg<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        // the first instruction, jump targets and the instructions after jumps
+        let mut leaders = vec![false; program.len()];
+        for (pc, instruction) in program.iter().enumerate() {
+            if let Instruction::Jmp(_) = instruction {
+                if let Some(target) = index(program, instruction.next_pc(pc)) {
+                    leaders[target] = true;
+                }
+                if let Some(next) = leaders.get_mut(pc + 1) {
+                    *next = true;
+                }
+            }
+        }
+        if let Some(first) = leaders.first_mut() {
+            *first = true;
+        }
+
+        // the block of every instruction
+        let mut block_of = Vec::with_capacity(program.len());
+        for &leader in &leaders {
+            let previous = block_of.last().copied();
+            block_of.push(match previous {
+                Some(block) if !leader => block,
+                Some(block) => block + 1,
+                None => 0,
+            });
+        }
+
+        let starts: Vec<_> = (0..program.len()).filter(|&pc| leaders[pc]).collect();
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let end = starts.get(i + 1).copied().unwrap_or(program.len());
+                let last = &program[end - 1];
+                let next = last.next_pc(end - 1);
+
+                Block {
+                    start,
+                    end,
+                    target: match index(program, next) {
+                        Some(pc) => Target::Block(block_of[pc]),
+                        None if next == program.len() as isize => Target::End,
+                        None => Target::OutOfBounds(next),
+                    },
+                    flow: match last {
+                        Instruction::Jmp(_) => Flow::Jump,
+                        _ => Flow::Fallthrough,
+                    },
+                }
+            })
+            .collect();
+
+        Self { program, blocks }
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// The blocks in the order they are executed, starting with the first block,
+    /// every block is listed once even if the program loops
+    pub fn path(&self) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut visited = vec![false; self.blocks.len()];
+        let mut block = if self.blocks.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+
+        while let Some(current) = block.filter(|&current| !visited[current]) {
+            visited[current] = true;
+            path.push(current);
+            block = match self.blocks[current].target {
+                Target::Block(next) => Some(next),
+                Target::End | Target::OutOfBounds(_) => None,
+            };
+        }
+
+        path
+    }
+
+    /// Everything that is wrong with the program, ordered by pc
+    pub fn lints(&self) -> Vec<Lint> {
+        let path = self.path();
+        let mut lints = Vec::new();
+
+        let mut reachable = vec![false; self.blocks.len()];
+        for &block in &path {
+            reachable[block] = true;
+        }
+
+        // consecutive unreachable blocks are reported together
+        let mut unreachable: Option<(usize, usize)> = None;
+        for (block, &reachable) in self.blocks.iter().zip(&reachable) {
+            match unreachable {
+                _ if reachable => {
+                    if let Some((start, end)) = unreachable.take() {
+                        lints.push(Lint::Unreachable { start, end });
+                    }
+                }
+                Some((_, ref mut end)) => *end = block.end,
+                None => unreachable = Some((block.start, block.end)),
+            }
+        }
+        if let Some((start, end)) = unreachable {
+            lints.push(Lint::Unreachable { start, end });
+        }
+
+        for block in &self.blocks {
+            if let (Target::OutOfBounds(target), Flow::Jump) = (block.target, block.flow) {
+                lints.push(Lint::OutOfBounds {
+                    pc: block.end - 1,
+                    target,
+                });
+            }
+        }
+
+        // the path ends with the block jumping back into the loop
+        if let Some(Target::Block(next)) = path.last().map(|&last| self.blocks[last].target) {
+            let entry = path.iter().position(|&block| block == next).unwrap_or(0);
+            lints.push(Lint::InfiniteLoop {
+                pcs: path[entry..]
+                    .iter()
+                    .map(|&block| self.blocks[block].start)
+                    .collect(),
+            });
+        }
+
+        lints.sort_by_key(|lint| match lint {
+            Lint::Unreachable { start: pc, .. } | Lint::OutOfBounds { pc, .. } => *pc,
+            Lint::InfiniteLoop { pcs } => pcs[0],
+        });
+        lints
+    }
+
+    /// The graph in the DOT language of Graphviz, one node per block with its
+    /// disassembly, unreachable blocks are grey and jumps out of bounds red
+    pub fn to_dot(&self) -> String {
+        let mut reachable = vec![false; self.blocks.len()];
+        for block in self.path() {
+            reachable[block] = true;
+        }
+
+        let mut dot = String::from("digraph boot {\n    node [shape=box, fontname=monospace];\n");
+        dot.push_str("    end [shape=doublecircle];\n");
+
+        for (block, &reachable) in self.blocks.iter().zip(&reachable) {
+            let mut label = String::new();
+            for pc in block.start..block.end {
+                label.push_str(&format!("{}: {}\\l", pc, self.program[pc]));
+            }
+            let color = if reachable {
+                ""
+            } else {
+                ", color=grey, fontcolor=grey"
+            };
+            dot.push_str(&format!(
+                "    b{} [label=\"{}\"{}];\n",
+                block.start, label, color
+            ));
+        }
+
+        for block in &self.blocks {
+            let style = match block.flow {
+                Flow::Jump => "",
+                Flow::Fallthrough => " [style=dashed]",
+            };
+            let target = match block.target {
+                Target::Block(next) => format!("b{}", self.blocks[next].start),
+                Target::End => "end".to_string(),
+                Target::OutOfBounds(pc) => {
+                    dot.push_str(&format!(
+                        "    out{} [label=\"{}\", shape=octagon, color=red];\n",
+                        block.start, pc
+                    ));
+                    format!("out{}", block.start)
+                }
+            };
+            dot.push_str(&format!("    b{} -> {}{};\n", block.start, target, style));
+        }
+
+        if let Some(first) = self.blocks.first() {
+            dot.push_str(&format!(
+                "    start [shape=point];\n    start -> b{};\n",
+                first.start
+            ));
+        } else {
+            dot.push_str("    start [shape=point];\n    start -> end;\n");
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// `pc` as an index into the program, `None` if it's outside of the program
+fn index(program: &[Instruction], pc: isize) -> Option<usize> {
+    usize::try_from(pc).ok().filter(|&pc| pc < program.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::sample;
+    use super::super::{Day, Input, META};
+    use super::*;
+    use crate::Day as _;
+
+    #[test]
+    fn blocks() {
+        let program = sample();
+        let cfg = Cfg::new(&program);
+
+        let blocks: Vec<_> = cfg
+            .blocks()
+            .iter()
+            .map(|block| (block.start, block.end, block.target))
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                (0, 1, Target::Block(1)),
+                (1, 3, Target::Block(4)),
+                (3, 5, Target::Block(1)),
+                (5, 6, Target::Block(4)),
+                (6, 8, Target::Block(2)),
+                (8, 9, Target::End),
+            ]
+        );
+        assert_eq!(cfg.blocks()[3].flow, Flow::Fallthrough);
+        assert_eq!(cfg.path(), vec![0, 1, 4, 2]);
+    }
+
+    #[test]
+    fn lints() {
+        let program = sample();
+        assert_eq!(
+            Cfg::new(&program).lints(),
+            vec![
+                Lint::InfiniteLoop { pcs: vec![1, 6, 3] },
+                Lint::Unreachable { start: 5, end: 6 },
+                Lint::Unreachable { start: 8, end: 9 },
+            ]
+        );
+
+        let program = vec![
+            Instruction::Jmp(2),
+            Instruction::Acc(1),
+            Instruction::Nop(0),
+            Instruction::Jmp(-4),
+        ];
+        let lints = Cfg::new(&program).lints();
+        assert_eq!(
+            lints,
+            vec![
+                Lint::Unreachable { start: 1, end: 2 },
+                Lint::OutOfBounds { pc: 3, target: -1 },
+            ]
+        );
+        assert_eq!(lints[0].to_string(), "pc 1: unreachable instruction");
+        assert_eq!(lints[1].to_string(), "pc 3: jump out of the program to -1");
+        assert_eq!(
+            Lint::Unreachable { start: 5, end: 9 }.to_string(),
+            "pc 5-8: unreachable instructions"
+        );
+
+        // the repaired program is fine
+        let mut program = Day.parse(&Input(META.input)).unwrap();
+        assert!(!Cfg::new(&program).lints().is_empty());
+        let switch = Instruction::repair(&program).unwrap();
+        program[switch].switch_nop_jmp();
+        assert!(!Cfg::new(&program)
+            .lints()
+            .iter()
+            .any(|lint| matches!(lint, Lint::InfiniteLoop { .. })));
+
+        assert_eq!(Cfg::new(&[]).lints(), vec![]);
+    }
+
+    #[test]
+    fn dot() {
+        let program = vec![Instruction::Nop(0), Instruction::Jmp(-5)];
+        assert_eq!(
+            Cfg::new(&program).to_dot(),
+            "digraph boot {
+    node [shape=box, fontname=monospace];
+    end [shape=doublecircle];
+    b0 [label=\"0: nop +0\\l1: jmp -5\\l\"];
+    out0 [label=\"-4\", shape=octagon, color=red];
+    b0 -> out0;
+    start [shape=point];
+    start -> b0;
+}
+"
+        );
+    }
+}
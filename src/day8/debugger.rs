@@ -0,0 +1,446 @@
+//! An interactive debugger for the handheld's boot code.
+//!
+//! `repl` reads commands line by line, see `HELP` for the available commands.
+//! An empty line repeats the previous command.
+
+use super::{Instruction, Kind, Process};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// `continue` gives up after this many instructions without a reason to stop
+pub const LIMIT: usize = 1_000_000;
+
+pub const HELP: &str = "\
+Commands:
+    s, step [N]          Execute N instructions (default: 1)
+    c, continue          Run until a breakpoint, the program ends or loops
+    b, break <PC|KIND>   Stop before the instruction at PC or any instruction
+                         of KIND (nop, acc or jmp)
+    d, delete <PC|KIND>  Remove a breakpoint
+    i, info              List the breakpoints
+    w, watch             Toggle printing every change of acc
+    l, list [N]          Disassemble N instructions around pc (default: 3)
+    p, print             Print pc and acc
+    r, reset             Restart the program
+    h, help              Print this help
+    q, quit              Leave the debugger";
+
+/// Where to stop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    Pc(usize),
+    Kind(Kind),
+}
+
+impl Breakpoint {
+    pub fn matches(&self, pc: usize, instruction: &Instruction) -> bool {
+        match self {
+            Self::Pc(at) => *at == pc,
+            Self::Kind(kind) => *kind == instruction.kind(),
+        }
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match (s.parse(), s.parse()) {
+            (Ok(pc), _) => Ok(Self::Pc(pc)),
+            (_, Ok(kind)) => Ok(Self::Kind(kind)),
+            _ => Err(format!(
+                "Invalid breakpoint '{}' (expected a pc, nop, acc or jmp)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pc(pc) => write!(f, "pc {}", pc),
+            Self::Kind(kind) => write!(f, "every {}", kind),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Step(usize),
+    Continue,
+    Break(Breakpoint),
+    Delete(Breakpoint),
+    Info,
+    Watch,
+    List(usize),
+    Print,
+    Reset,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<_> = s.split_whitespace().collect();
+        let number = |n: &str| n.parse().map_err(|_| format!("Invalid number '{}'", n));
+
+        match words[..] {
+            ["s"] | ["step"] => Ok(Self::Step(1)),
+            ["s", n] | ["step", n] => match number(n)? {
+                0 => Err("Can't step 0 instructions".to_string()),
+                n => Ok(Self::Step(n)),
+            },
+            ["c"] | ["continue"] => Ok(Self::Continue),
+            ["b", at] | ["break", at] => Ok(Self::Break(at.parse()?)),
+            ["d", at] | ["delete", at] => Ok(Self::Delete(at.parse()?)),
+            ["i"] | ["info"] => Ok(Self::Info),
+            ["w"] | ["watch"] => Ok(Self::Watch),
+            ["l"] | ["list"] => Ok(Self::List(3)),
+            ["l", n] | ["list", n] => Ok(Self::List(number(n)?)),
+            ["p"] | ["print"] => Ok(Self::Print),
+            ["r"] | ["reset"] => Ok(Self::Reset),
+            ["h"] | ["help"] => Ok(Self::Help),
+            ["q"] | ["quit"] => Ok(Self::Quit),
+            _ => Err(format!("Unknown command '{}', try 'help'", s.trim())),
+        }
+    }
+}
+
+/// Why the program stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The requested number of instructions has been executed
+    Step,
+    Breakpoint(Breakpoint),
+    /// The next instruction has been executed before, the program loops forever
+    Repeat,
+    /// The program ended by moving past its last instruction
+    Terminated,
+    /// The program jumped outside of itself
+    OutOfBounds,
+    /// `continue` executed `LIMIT` instructions
+    Limit,
+}
+
+#[derive(Clone, Debug)]
+pub struct Debugger<'a> {
+    process: Process<'a>,
+    breakpoints: Vec<Breakpoint>,
+    watch: bool,
+    /// How often the instruction at every pc has been executed
+    executed: Vec<usize>,
+    /// Whether the first repeated instruction has been reported
+    repeated: bool,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        Self {
+            process: Process::new(program),
+            breakpoints: Vec::new(),
+            watch: false,
+            executed: vec![0; program.len()],
+            repeated: false,
+        }
+    }
+
+    pub fn process(&self) -> &Process<'a> {
+        &self.process
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Execute at most `steps` instructions, or until there is a reason to stop.
+    /// Changes of acc are written to `out` while watching.
+    pub fn run<W: Write>(&mut self, steps: Option<usize>, out: &mut W) -> io::Result<Stop> {
+        let limit = steps.unwrap_or(LIMIT);
+        let mut done = 0;
+
+        loop {
+            let pc = match self.process.index() {
+                Some(pc) => pc,
+                None if self.process.terminated() => return Ok(Stop::Terminated),
+                None => return Ok(Stop::OutOfBounds),
+            };
+
+            let acc = self.process.acc;
+            self.executed[pc] += 1;
+            self.process.step();
+            done += 1;
+
+            if self.watch && acc != self.process.acc {
+                writeln!(out, "acc {} -> {} at pc {}", acc, self.process.acc, pc)?;
+            }
+
+            // look at the instruction which is executed next
+            let next = match self.process.index() {
+                Some(next) => next,
+                None => continue,
+            };
+            let instruction = &self.process.program[next];
+
+            if self.executed[next] > 0 && !self.repeated {
+                self.repeated = true;
+                return Ok(Stop::Repeat);
+            }
+            if let Some(breakpoint) = self
+                .breakpoints
+                .iter()
+                .find(|breakpoint| breakpoint.matches(next, instruction))
+            {
+                return Ok(Stop::Breakpoint(*breakpoint));
+            }
+            if done >= limit {
+                return Ok(if steps.is_some() {
+                    Stop::Step
+                } else {
+                    Stop::Limit
+                });
+            }
+        }
+    }
+
+    /// Write the instructions up to `context` lines around the pc, the current one is
+    /// marked with `=>` and breakpoints with `*`
+    pub fn list<W: Write>(&self, context: usize, out: &mut W) -> io::Result<()> {
+        let program = self.process.program;
+        let pc = self.process.pc.clamp(0, program.len() as isize) as usize;
+        let end = (pc + context + 1).min(program.len());
+        let index = self.process.index();
+
+        for (i, instruction) in program
+            .iter()
+            .enumerate()
+            .take(end)
+            .skip(pc.saturating_sub(context))
+        {
+            let current = if index == Some(i) { "=>" } else { "  " };
+            let breakpoint = if self.breakpoints.iter().any(|b| b.matches(i, instruction)) {
+                '*'
+            } else {
+                ' '
+            };
+            writeln!(out, "{}{}{:>4}: {}", current, breakpoint, i, instruction)?;
+        }
+
+        Ok(())
+    }
+
+    fn print<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let Process { pc, acc, .. } = self.process;
+        match self.process.index() {
+            Some(i) => writeln!(out, "pc {} ({}) acc {}", pc, self.process.program[i], acc),
+            None => writeln!(out, "pc {} (outside of the program) acc {}", pc, acc),
+        }
+    }
+
+    /// Execute a command, returns false if the debugger should quit
+    pub fn execute<W: Write>(&mut self, command: Command, out: &mut W) -> io::Result<bool> {
+        match command {
+            Command::Step(steps) => {
+                let stop = self.run(Some(steps), out)?;
+                self.report(stop, out)?;
+            }
+            Command::Continue => {
+                let stop = self.run(None, out)?;
+                self.report(stop, out)?;
+            }
+            Command::Break(breakpoint) if self.breakpoints.contains(&breakpoint) => {
+                writeln!(out, "There is a breakpoint at {} already", breakpoint)?
+            }
+            Command::Break(breakpoint) => {
+                self.breakpoints.push(breakpoint);
+                writeln!(out, "Breakpoint at {}", breakpoint)?;
+            }
+            Command::Delete(breakpoint) => {
+                match self.breakpoints.iter().position(|b| *b == breakpoint) {
+                    Some(i) => {
+                        self.breakpoints.remove(i);
+                        writeln!(out, "Deleted the breakpoint at {}", breakpoint)?;
+                    }
+                    None => writeln!(out, "There is no breakpoint at {}", breakpoint)?,
+                }
+            }
+            Command::Info if self.breakpoints.is_empty() => writeln!(out, "No breakpoints")?,
+            Command::Info => {
+                for breakpoint in &self.breakpoints {
+                    writeln!(out, "Breakpoint at {}", breakpoint)?;
+                }
+            }
+            Command::Watch => {
+                self.watch = !self.watch;
+                let state = if self.watch {
+                    "Watching"
+                } else {
+                    "Not watching"
+                };
+                writeln!(out, "{} acc", state)?;
+            }
+            Command::List(context) => self.list(context, out)?,
+            Command::Print => self.print(out)?,
+            Command::Reset => {
+                self.process = Process::new(self.process.program);
+                self.executed.iter_mut().for_each(|count| *count = 0);
+                self.repeated = false;
+                self.print(out)?;
+            }
+            Command::Help => writeln!(out, "{}", HELP)?,
+            Command::Quit => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn report<W: Write>(&self, stop: Stop, out: &mut W) -> io::Result<()> {
+        let Process { pc, acc, .. } = self.process;
+        match stop {
+            Stop::Step => (),
+            Stop::Breakpoint(breakpoint) => writeln!(out, "Breakpoint at {}", breakpoint)?,
+            Stop::Repeat => writeln!(
+                out,
+                "The instruction at pc {} is about to execute a second time, the program loops",
+                pc
+            )?,
+            Stop::Terminated => return writeln!(out, "The program terminated with acc {}", acc),
+            Stop::OutOfBounds => {
+                return writeln!(out, "The program jumped to pc {} with acc {}", pc, acc)
+            }
+            Stop::Limit => writeln!(out, "Stopped after {} instructions", LIMIT)?,
+        }
+        self.print(out)
+    }
+}
+
+/// Debug `program`, reading commands from `input` until it ends or `quit`
+pub fn repl<R, W>(program: &[Instruction], input: R, mut out: W) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut debugger = Debugger::new(program);
+    let mut last = None;
+
+    writeln!(
+        out,
+        "{} instructions, 'help' lists the commands",
+        program.len()
+    )?;
+    debugger.list(3, &mut out)?;
+
+    let mut lines = input.lines();
+    loop {
+        write!(out, "(debug) ")?;
+        out.flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        let command = match (line.trim(), last) {
+            ("", Some(last)) => Ok(last),
+            ("", None) => continue,
+            (line, _) => line.parse(),
+        };
+
+        match command {
+            Ok(command) => {
+                last = Some(command);
+                if !debugger.execute(command, &mut out)? {
+                    break;
+                }
+            }
+            Err(e) => writeln!(out, "{}", e)?,
+        }
+    }
+
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::sample;
+    use super::*;
+
+    fn session_with(program: &[Instruction], commands: &str) -> String {
+        let mut out = Vec::new();
+        repl(program, commands.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn session(commands: &str) -> String {
+        session_with(&sample(), commands)
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!("s".parse(), Ok(Command::Step(1)));
+        assert_eq!("step 5".parse(), Ok(Command::Step(5)));
+        assert_eq!(" b  4 ".parse(), Ok(Command::Break(Breakpoint::Pc(4))));
+        assert_eq!(
+            "delete jmp".parse(),
+            Ok(Command::Delete(Breakpoint::Kind(Kind::Jmp)))
+        );
+        assert!("break".parse::<Command>().is_err());
+        assert!("break mul".parse::<Command>().is_err());
+        assert!("step x".parse::<Command>().is_err());
+        assert!("s 0".parse::<Command>().is_err());
+        assert!("frobnicate".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn breakpoints() {
+        let out = session("b 3\nb acc\nc\nc\nd acc\ni\nc\nc\n");
+
+        assert!(out.contains("Breakpoint at every acc\npc 1 (acc +1) acc 0\n"));
+        assert!(out.contains("Breakpoint at every acc\npc 6 (acc +1) acc 1\n"));
+        assert!(out.contains("Deleted the breakpoint at every acc\n(debug) Breakpoint at pc 3\n"));
+        assert!(out.contains("Breakpoint at pc 3\npc 3 (acc +3) acc 2\n"));
+        assert!(out.contains(
+            "The instruction at pc 1 is about to execute a second time, the program loops\n\
+             pc 1 (acc +1) acc 5\n"
+        ));
+    }
+
+    #[test]
+    fn repeated_instruction() {
+        let program = sample();
+        let mut debugger = Debugger::new(&program);
+        let mut out = Vec::new();
+
+        assert_eq!(debugger.run(None, &mut out).unwrap(), Stop::Repeat);
+        assert_eq!((debugger.process().pc, debugger.process().acc), (1, 5));
+        // the loop is only reported once
+        assert_eq!(debugger.run(Some(7), &mut out).unwrap(), Stop::Step);
+        assert_eq!(debugger.run(None, &mut out).unwrap(), Stop::Limit);
+    }
+
+    #[test]
+    fn stepping_and_watching() {
+        let out = session("w\ns 2\n\nl 1\nr\nq\ns\n");
+
+        assert!(out.contains("acc 0 -> 1 at pc 1\npc 2 (jmp +4) acc 1\n"));
+        assert!(out.contains("acc 1 -> 2 at pc 6\npc 7 (jmp -4) acc 2\n"));
+        assert!(out.contains("      6: acc +1\n=>    7: jmp -4\n      8: acc +6\n"));
+        assert!(out.contains("(debug) pc 0 (nop +0) acc 0\n(debug) \n"));
+
+        // stepping 0 instructions after the loop was reported doesn't run forever
+        let out = session("c\ns 0\nq\n");
+        assert!(out.contains("Can't step 0 instructions\n"));
+    }
+
+    #[test]
+    fn termination() {
+        let mut program = sample();
+        program[7] = Instruction::Nop(-4);
+        let out = session_with(&program, "c\np\n");
+        assert!(out.contains("The program terminated with acc 8\n"));
+        assert!(out.contains("pc 9 (outside of the program) acc 8\n"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Tracing the execution of the boot code.
+//!
+//! Tracing is opt-in: a `Trace` records the instructions it executes through
+//! `Trace::step`, processes stepped directly are not traced.
+
+use super::{Instruction, Kind, Process, State};
+use crate::report::json_string;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// An executed instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub acc_before: i32,
+    pub acc_after: i32,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.pc, self.instruction, self.acc_before, self.acc_after
+        )
+    }
+}
+
+/// The executed instructions in the order of execution
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub events: Vec<Event>,
+}
+
+impl Trace {
+    /// Trace `program` until it terminates or is about to execute an instruction
+    /// for the `loops + 1`th time, so a loop is followed `loops` times
+    pub fn run(program: &[Instruction], loops: usize) -> Self {
+        let mut trace = Self::default();
+        let mut process = Process::new(program);
+        let mut executed = vec![0; program.len()];
+
+        while let Some(pc) = process.index() {
+            if executed[pc] >= loops {
+                break;
+            }
+            executed[pc] += 1;
+            trace.step(&mut process);
+        }
+
+        trace
+    }
+
+    /// Execute the next instruction of `process` and record it
+    pub fn step(&mut self, process: &mut Process) -> State {
+        let pc = process.index();
+        let instruction = pc.map(|pc| process.program[pc]);
+        let acc_before = process.acc;
+
+        let state = process.step();
+        if let (Some(pc), Some(instruction)) = (pc, instruction) {
+            self.events.push(Event {
+                pc,
+                instruction,
+                acc_before,
+                acc_after: process.acc,
+            });
+        }
+
+        state
+    }
+
+    /// The trace as text, one `<pc> <instruction> <acc before> <acc after>` line
+    /// per executed instruction
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# pc instruction acc_before acc_after\n");
+        for event in &self.events {
+            text.push_str(&format!("{}\n", event));
+        }
+        text
+    }
+
+    /// The trace as a JSON array of events
+    pub fn to_json(&self) -> String {
+        let events: Vec<_> = self
+            .events
+            .iter()
+            .map(|event| {
+                format!(
+                    "  {{\"pc\": {}, \"instruction\": {}, \"acc_before\": {}, \"acc_after\": {}}}",
+                    event.pc,
+                    json_string(&event.instruction.to_string()),
+                    event.acc_before,
+                    event.acc_after
+                )
+            })
+            .collect();
+
+        if events.is_empty() {
+            "[]\n".to_string()
+        } else {
+            format!("[\n{}\n]\n", events.join(",\n"))
+        }
+    }
+
+    /// Summary statistics of the trace
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+
+        for event in &self.events {
+            stats.executed += 1;
+            *stats.kinds.entry(event.instruction.kind()).or_default() += 1;
+            stats
+                .pcs
+                .entry(event.pc)
+                .or_insert((event.instruction, 0))
+                .1 += 1;
+        }
+
+        stats
+    }
+}
+
+/// Summary statistics of a trace
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of executed instructions
+    pub executed: usize,
+    /// Number of executed instructions of every kind
+    pub kinds: BTreeMap<Kind, usize>,
+    /// The instruction at every executed pc and how often it was executed
+    pub pcs: BTreeMap<usize, (Instruction, usize)>,
+}
+
+impl Stats {
+    /// The `n` most executed instructions as (pc, instruction, executions),
+    /// the lower pc comes first on ties
+    pub fn hot(&self, n: usize) -> Vec<(usize, Instruction, usize)> {
+        let mut hot: Vec<_> = self
+            .pcs
+            .iter()
+            .map(|(&pc, &(instruction, count))| (pc, instruction, count))
+            .collect();
+        hot.sort_by_key(|&(pc, _, count)| (std::cmp::Reverse(count), pc));
+        hot.truncate(n);
+        hot
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} instructions executed at {} pcs",
+            self.executed,
+            self.pcs.len()
+        )?;
+        for (kind, count) in &self.kinds {
+            writeln!(f, "{} {:>8}", kind, count)?;
+        }
+
+        writeln!(f, "hot instructions:")?;
+        for (pc, instruction, count) in self.hot(10) {
+            writeln!(
+                f,
+                "{:>6}: {:<10} {:>8}x",
+                pc,
+                instruction.to_string(),
+                count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::sample;
+    use super::*;
+
+    #[test]
+    fn trace() {
+        let trace = Trace::run(&sample(), 1);
+
+        let pcs: Vec<_> = trace.events.iter().map(|event| event.pc).collect();
+        assert_eq!(pcs, vec![0, 1, 2, 6, 7, 3, 4]);
+        assert_eq!(
+            trace.events[3],
+            Event {
+                pc: 6,
+                instruction: Instruction::Acc(1),
+                acc_before: 1,
+                acc_after: 2,
+            }
+        );
+
+        assert_eq!(
+            trace.to_text().lines().take(3).collect::<Vec<_>>(),
+            vec![
+                "# pc instruction acc_before acc_after",
+                "0 nop +0 0 0",
+                "1 acc +1 0 1"
+            ]
+        );
+        assert!(trace.to_json().starts_with(
+            "[\n  {\"pc\": 0, \"instruction\": \"nop +0\", \"acc_before\": 0, \"acc_after\": 0},\n"
+        ));
+        assert_eq!(Trace::default().to_json(), "[]\n");
+    }
+
+    #[test]
+    fn stats() {
+        // the loop runs through the pcs 1, 2, 6, 7, 3 and 4
+        let stats = Trace::run(&sample(), 3).stats();
+        assert_eq!(stats.executed, 19);
+        assert_eq!(stats.hot(2), vec![(1, Instruction::Acc(1), 3), (2, Instruction::Jmp(4), 3)]);
+        assert_eq!(stats.pcs[&0], (Instruction::Nop(0), 1));
+
+        let program = sample();
+        let mut trace = Trace::default();
+        let mut process = Process::new(&program);
+        for _ in 0..12 {
+            trace.step(&mut process);
+        }
+
+        let stats = trace.stats();
+        assert_eq!(stats.executed, 12);
+        assert_eq!(stats.kinds[&Kind::Jmp], 5);
+        assert_eq!(stats.pcs.len(), 7);
+        assert_eq!(
+            stats.hot(3),
+            vec![
+                (1, Instruction::Acc(1), 2),
+                (2, Instruction::Jmp(4), 2),
+                (3, Instruction::Acc(3), 2),
+            ]
+        );
+        assert!(stats.to_string().starts_with(
+            "12 instructions executed at 7 pcs\nnop        1\nacc        6\njmp        5\n"
+        ));
+    }
+}
@@ -1,8 +1,15 @@
-use super::{Day as DayTrait, Input};
+use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
 use itertools::Itertools;
 use std::ops::{Add, AddAssign};
 
-fn first_invalid<T>(values: &[T], window_size: usize) -> Option<T>
+pub const META: Meta = Meta {
+    day: 9,
+    title: "Encoding Error",
+    input: include_str!("inputs/9"),
+};
+
+/// Find the first value which is not the sum of two of the `window_size` values before it
+pub fn first_invalid<T>(values: &[T], window_size: usize) -> Option<T>
 where
     T: Copy + Default + PartialEq + Add<Output = T>,
 {
@@ -22,7 +29,7 @@ where
 }
 
 /// Find the first sub-range of values which's sum equals target
-fn find_range<T>(values: &[T], target: T) -> Option<&[T]>
+pub fn find_range<T>(values: &[T], target: T) -> Option<&[T]>
 where
     T: Copy + Default + PartialEq + PartialOrd + AddAssign,
 {
@@ -45,7 +52,7 @@ where
 }
 
 /// Calculate the sum of the smallest and the biggest value in values
-fn min_max_sum<T>(values: &[T]) -> Option<T>
+pub fn min_max_sum<T>(values: &[T]) -> Option<T>
 where
     T: Copy + Ord + Add<Output = T>,
 {
@@ -61,27 +68,23 @@ where
 pub struct Day;
 
 impl DayTrait for Day {
-    fn part1(&self, input: &Input) -> String {
-        let values: Vec<_> = input
-            .0
-            .lines()
-            .filter_map(|line| line.parse::<u64>().ok())
-            .collect();
-
-        first_invalid(&values, 25).unwrap().to_string()
+    type Parsed<'i> = Vec<u64>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        input.parse_lines()
     }
 
-    fn part2(&self, input: &Input) -> String {
-        let values: Vec<_> = input
-            .0
-            .lines()
-            .filter_map(|line| line.parse::<u64>().ok())
-            .collect();
+    fn part1(&self, values: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let invalid = first_invalid(values, 25).ok_or(SolveError::NoSolution)?;
+        Ok(invalid.into())
+    }
 
-        let invalid = first_invalid(&values, 25).unwrap();
-        let range = find_range(&values, invalid).unwrap();
+    fn part2(&self, values: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        let invalid = first_invalid(values, 25).ok_or(SolveError::NoSolution)?;
+        let range = find_range(values, invalid).ok_or(SolveError::NoSolution)?;
+        let sum = min_max_sum(range).ok_or(SolveError::NoSolution)?;
 
-        min_max_sum(range).unwrap().to_string()
+        Ok(sum.into())
     }
 }
 
@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// The reasons a part can fail to produce an answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The input could not be parsed, `line` and `column` start at 1
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input is valid, but there is no answer for it
+    NoSolution,
+    /// The input was parsed, but does not have the expected shape
+    InvalidInput(String),
+}
+
+impl SolveError {
+    pub fn parse<M: Into<String>>(line: usize, column: usize, message: M) -> Self {
+        Self::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid<M: Into<String>>(message: M) -> Self {
+        Self::InvalidInput(message.into())
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Parse error at {}:{}: {}", line, column, message),
+            Self::NoSolution => write!(f, "No solution found"),
+            Self::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
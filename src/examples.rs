@@ -0,0 +1,260 @@
+//! Example inputs from the puzzle descriptions together with their expected answers.
+//!
+//! The examples of a day are kept in `src/examples/<day>`, every example starts with a
+//! `== <name>` line, followed by optional `part1 <answer>` and `part2 <answer>` lines
+//! and the input, which starts after a `--` line:
+//!
+//! ```text
+//! # comments are allowed before the input
+//! == sample
+//! part1 5
+//! part2 8
+//! --
+//! nop +0
+//! acc +1
+//! ```
+//!
+//! Answers are written like in the answers manifest. Trailing blank lines of an input
+//! are ignored, every example needs a test in `tests/examples.rs`.
+
+use crate::{Answer, Entry, Input};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+impl Example {
+    /// The expected answer of `part`, if any
+    pub fn expected(&self, part: usize) -> Option<&Answer> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the examples of a day
+pub fn parse(s: &str) -> Result<Vec<Example>, String> {
+    let mut examples: Vec<Example> = Vec::new();
+    let mut in_input = false;
+
+    for (n, line) in s.lines().enumerate() {
+        let error = |message: &str| format!("line {}: {}", n + 1, message);
+
+        if let Some(name) = line.strip_prefix("== ") {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(error("missing example name"));
+            }
+            if examples.iter().any(|example| example.name == name) {
+                return Err(error("duplicate example name"));
+            }
+
+            examples.push(Example {
+                name: name.to_string(),
+                input: String::new(),
+                part1: None,
+                part2: None,
+            });
+            in_input = false;
+            continue;
+        }
+
+        let example = match examples.last_mut() {
+            Some(example) => example,
+            None if line.trim().is_empty() || line.starts_with('#') => continue,
+            None => return Err(error("expected '== <name>'")),
+        };
+
+        if in_input {
+            example.input.push_str(line);
+            example.input.push('\n');
+            continue;
+        }
+
+        let line = line.trim();
+        if line == "--" {
+            in_input = true;
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else {
+            let parse_answer = |answer: &str| {
+                Some(
+                    answer
+                        .trim()
+                        .replace("\\n", "\n")
+                        .parse()
+                        .unwrap_or_else(|e| match e {}),
+                )
+            };
+
+            match line.split_once(char::is_whitespace) {
+                Some(("part1", answer)) if example.part1.is_none() => {
+                    example.part1 = parse_answer(answer)
+                }
+                Some(("part2", answer)) if example.part2.is_none() => {
+                    example.part2 = parse_answer(answer)
+                }
+                _ => return Err(error("expected 'part1 <answer>', 'part2 <answer>' or '--'")),
+            }
+        }
+    }
+
+    for example in examples.iter_mut() {
+        let len = example.input.trim_end().len();
+        example.input.truncate(len);
+        if !example.input.is_empty() {
+            example.input.push('\n');
+        }
+    }
+
+    match examples.iter().find(|example| example.input.is_empty()) {
+        Some(example) => Err(format!("example '{}' has no input", example.name)),
+        None => Ok(examples),
+    }
+}
+
+/// Where the examples of `day` are kept in `dir`
+pub fn path(dir: &Path, day: usize) -> PathBuf {
+    dir.join(day.to_string())
+}
+
+/// Load the examples of `day` from `dir`, a day without examples has none
+pub fn load(dir: &Path, day: usize) -> Result<Vec<Example>, String> {
+    let path = path(dir, day);
+
+    match std::fs::read_to_string(&path) {
+        Ok(examples) => parse(&examples).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Can't read {}: {}", path.display(), e)),
+    }
+}
+
+/// The answer of a part, or why it could not be solved
+pub type PartAnswer = Result<Answer, String>;
+
+/// Solve `example` with the solver of `entry`, returns the answer of every part
+/// with an expected answer, or why the input could not be parsed
+pub fn solve(entry: &Entry, example: &Example) -> Result<Vec<(usize, PartAnswer)>, String> {
+    let parsed = entry
+        .solver
+        .parse(&Input(&example.input))
+        .map_err(|e| e.to_string())?;
+
+    Ok((1..=2)
+        .filter(|&part| example.expected(part).is_some())
+        .map(|part| (part, parsed.solve(part).map_err(|e| e.to_string())))
+        .collect())
+}
+
+/// Check the answers of `example`, describes every mismatch on failure
+pub fn check(entry: &Entry, example: &Example) -> Result<(), String> {
+    let mismatches: Vec<_> = solve(entry, example)?
+        .into_iter()
+        .filter_map(|(part, answer)| {
+            let expected = example.expected(part)?;
+            match answer {
+                Ok(answer) if &answer == expected => None,
+                Ok(answer) => Some(format!("part{}: {} (expected {})", part, answer, expected)),
+                Err(e) => Some(format!("part{}: {} (expected {})", part, e, expected)),
+            }
+        })
+        .collect();
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "day{:0>2} example '{}':\n{}",
+            entry.day,
+            example.name,
+            mismatches.join("\n")
+        ))
+    }
+}
+
+/// Check the example `name` in `examples` (the contents of an examples file) of `day`,
+/// used by the tests in `tests/examples.rs`
+pub fn run(day: usize, name: &str, examples: &str) -> Result<(), String> {
+    let entry = crate::day(day).ok_or_else(|| format!("There is no day {}", day))?;
+    let examples = parse(examples)?;
+    let example = examples
+        .iter()
+        .find(|example| example.name == name)
+        .ok_or_else(|| format!("There is no example '{}' for day {}", name, day))?;
+
+    check(&entry, example)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_examples() {
+        let examples = parse(
+            "# comment\n\
+             == first\n\
+             part1 42\n\
+             --\n\
+             #..\n\
+             \n\
+             .#.\n\
+             \n\
+             \n\
+             == second\n\
+             part2 #.\\n.#\n\
+             --\n\
+             1\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            examples,
+            vec![
+                Example {
+                    name: "first".to_string(),
+                    input: "#..\n\n.#.\n".to_string(),
+                    part1: Some(Answer::Integer(42)),
+                    part2: None,
+                },
+                Example {
+                    name: "second".to_string(),
+                    input: "1\n".to_string(),
+                    part1: None,
+                    part2: Some("#.\n.#".into()),
+                },
+            ]
+        );
+
+        assert!(parse("part1 42\n--\n1\n").is_err());
+        assert!(parse("== a\n--\n1\n== a\n--\n2\n").is_err());
+        assert!(parse("== a\npart3 1\n--\n1\n").is_err());
+        assert!(parse("== a\npart1 1\n").is_err());
+        assert!(parse("== a\n--\n\n").is_err());
+    }
+
+    #[test]
+    fn mismatch() {
+        let entry = crate::day(1).unwrap();
+        let example = |part2: i64| Example {
+            name: "sample".to_string(),
+            input: "1721\n979\n366\n299\n675\n1456\n".to_string(),
+            part1: Some(514_579.into()),
+            part2: Some(part2.into()),
+        };
+
+        assert_eq!(check(&entry, &example(241_861_950)), Ok(()));
+        assert_eq!(
+            check(&entry, &example(1)),
+            Err("day01 example 'sample':\npart2: 241861950 (expected 1)".to_string())
+        );
+    }
+}
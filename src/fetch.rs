@@ -0,0 +1,296 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable holding the session cookie of adventofcode.com
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// Environment variable which can be used instead of `--url <URL>`
+pub const URL_ENV: &str = "AOC_URL";
+pub const DEFAULT_URL: &str = "https://adventofcode.com/2020";
+
+const USER_AGENT: &str = "aoc-rs (https://github.com/allgoewer/aoc-2020-rs)";
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// Neither `$AOC_SESSION` nor the session file are set
+    NoSession {
+        path: Option<PathBuf>,
+    },
+    /// The server answered, but not with the input
+    Status {
+        day: usize,
+        status: u16,
+    },
+    /// The server could not be reached
+    Transport {
+        day: usize,
+        message: String,
+    },
+    Io {
+        path: PathBuf,
+        err: io::Error,
+    },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSession { path: Some(path) } => write!(
+                f,
+                "No session token, set ${} or write it to {}",
+                SESSION_ENV,
+                path.display()
+            ),
+            Self::NoSession { path: None } => write!(f, "No session token, set ${}", SESSION_ENV),
+            Self::Status { day, status: 400 } | Self::Status { day, status: 401 } => write!(
+                f,
+                "Request for day {} failed, the session token was rejected",
+                day
+            ),
+            Self::Status { day, status: 404 } => write!(
+                f,
+                "Request for day {} failed, the puzzle is not available (yet)",
+                day
+            ),
+            Self::Status { day, status } => write!(
+                f,
+                "Request for day {} failed, the server answered with status {}",
+                day, status
+            ),
+            Self::Transport { day, message } => {
+                write!(f, "Request for day {} failed: {}", day, message)
+            }
+            Self::Io { path, err } => write!(f, "Can't write {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// The file the session token is read from if `$AOC_SESSION` is not set,
+/// `$XDG_CONFIG_HOME/aoc-rs/session` or `~/.config/aoc-rs/session`
+pub fn session_file() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(config.join("aoc-rs").join("session"))
+}
+
+/// Read the session token from `$AOC_SESSION` or the session file
+pub fn session() -> Result<String, FetchError> {
+    let path = session_file();
+
+    std::env::var(SESSION_ENV)
+        .ok()
+        .or_else(|| fs::read_to_string(path.as_ref()?).ok())
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+        .ok_or(FetchError::NoSession { path })
+}
+
+/// Downloads inputs from and submits answers to an adventofcode.com compatible server
+pub struct Client {
+    url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// `url` is the address of the event, e.g. `DEFAULT_URL`
+    pub fn new(url: &str, session: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    fn request(&self, method: &str, day: usize, path: &str) -> ureq::Request {
+        self.agent
+            .request(method, &format!("{}/day/{}{}", self.url, day, path))
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn body(
+        day: usize,
+        response: Result<ureq::Response, ureq::Error>,
+    ) -> Result<String, FetchError> {
+        let response = response.map_err(|e| match e {
+            ureq::Error::Status(status, _) => FetchError::Status { day, status },
+            ureq::Error::Transport(e) => FetchError::Transport {
+                day,
+                message: e.to_string(),
+            },
+        })?;
+
+        response.into_string().map_err(|e| FetchError::Transport {
+            day,
+            message: e.to_string(),
+        })
+    }
+
+    /// Download the input of `day`
+    pub fn input(&self, day: usize) -> Result<String, FetchError> {
+        Self::body(day, self.request("GET", day, "/input").call())
+    }
+
+    /// Submit `answer` for `part` of `day`, returns the page the server answered with
+    pub fn submit(&self, day: usize, part: usize, answer: &str) -> Result<String, FetchError> {
+        let level = part.to_string();
+        let form = [("level", level.as_str()), ("answer", answer)];
+
+        Self::body(day, self.request("POST", day, "/answer").send_form(&form))
+    }
+}
+
+/// Where the input of `day` is cached in `dir`, the same layout as `Source::Directory`
+pub fn cache_path(dir: &Path, day: usize) -> PathBuf {
+    dir.join(day.to_string())
+}
+
+/// Whether the input of `day` has been fetched already, an empty file
+/// (as created by `new`) doesn't count
+pub fn is_cached(dir: &Path, day: usize) -> bool {
+    fs::metadata(cache_path(dir, day)).is_ok_and(|meta| meta.len() > 0)
+}
+
+/// Download the input of `day` into `dir` unless it is cached there already.
+/// `client` is only called if the input has to be downloaded.
+/// Returns the path of the input and whether it was downloaded.
+pub fn fetch<C>(dir: &Path, day: usize, client: C) -> Result<(PathBuf, bool), FetchError>
+where
+    C: FnOnce() -> Result<Client, FetchError>,
+{
+    let path = cache_path(dir, day);
+    if is_cached(dir, day) {
+        return Ok((path, false));
+    }
+
+    let input = client()?.input(day)?;
+
+    // write to a temporary file first, an interrupted download must not look cached
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |err| FetchError::Io { path, err }
+    };
+    let partial = dir.join(format!(".{}.partial", day));
+    fs::create_dir_all(dir).map_err(io_error(dir))?;
+    fs::write(&partial, input).map_err(io_error(&partial))?;
+    fs::rename(&partial, &path).map_err(io_error(&path))?;
+
+    Ok((path, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve `responses` (status, body) to one connection each,
+    /// returns the address and the request line, cookie and body of every request
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/2020", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let header = line.to_lowercase();
+                    if line.trim().is_empty() {
+                        break;
+                    } else if let Some(value) = header.strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    } else if !header.contains(": ") || header.starts_with("cookie:") {
+                        request.push_str(line.trim());
+                        request.push(';');
+                    }
+                }
+
+                let mut form = vec![0; length];
+                reader.read_exact(&mut form).unwrap();
+                request.push_str(&String::from_utf8(form).unwrap());
+                requests.push(request);
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+
+            requests
+        });
+
+        (url, server)
+    }
+
+    #[test]
+    fn cached() {
+        let tmp = TempDir::new("fetch");
+        let dir = tmp.path();
+        fs::write(dir.join("3"), "").unwrap();
+
+        let (url, server) = serve(vec![(200, "..#\n#..\n"), (404, "Not found")]);
+        let client = || Ok(Client::new(&url, "secret"));
+
+        // the empty input of a new day is replaced
+        assert_eq!(fetch(dir, 3, client).unwrap(), (dir.join("3"), true));
+        assert_eq!(fs::read_to_string(dir.join("3")).unwrap(), "..#\n#..\n");
+
+        // a cached input is never downloaded again
+        let offline = || -> Result<Client, FetchError> { panic!("the input was downloaded again") };
+        assert_eq!(fetch(dir, 3, offline).unwrap(), (dir.join("3"), false));
+
+        assert!(matches!(
+            fetch(dir, 4, client),
+            Err(FetchError::Status {
+                day: 4,
+                status: 404
+            })
+        ));
+        assert!(!dir.join("4").exists());
+
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                "GET /2020/day/3/input HTTP/1.1;Cookie: session=secret;",
+                "GET /2020/day/4/input HTTP/1.1;Cookie: session=secret;",
+            ]
+        );
+    }
+
+    #[test]
+    fn submit() {
+        let (url, server) = serve(vec![(200, "<p>That's the right answer!</p>")]);
+
+        assert_eq!(
+            Client::new(&url, "secret").submit(8, 2, "758").unwrap(),
+            "<p>That's the right answer!</p>"
+        );
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["POST /2020/day/8/answer HTTP/1.1;Cookie: session=secret;level=2&answer=758"]
+        );
+    }
+}
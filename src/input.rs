@@ -0,0 +1,148 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Environment variable which can be used instead of `--inputs <DIR>`
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
+/// Environment variable overriding the directory containing the named input sets
+pub const SETS_ENV: &str = "AOC_SETS";
+
+/// Where the puzzle inputs are loaded from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// The inputs compiled into the binary
+    Embedded,
+    /// A directory containing one file per day, named after the day number
+    Directory(PathBuf),
+    /// A single file
+    File(PathBuf),
+    /// Standard input
+    Stdin,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Missing {
+        day: usize,
+        path: PathBuf,
+    },
+    Io {
+        day: usize,
+        path: PathBuf,
+        err: io::Error,
+    },
+    Stdin(io::Error),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { day, path } => write!(
+                f,
+                "Input for day {} not found (expected {})",
+                day,
+                path.display()
+            ),
+            Self::Io { day, path, err } => write!(
+                f,
+                "Can't read input for day {} from {}: {}",
+                day,
+                path.display(),
+                err
+            ),
+            Self::Stdin(err) => write!(f, "Can't read input from stdin: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl Source {
+    /// Load the input of `day`, `embedded` is used for `Source::Embedded`
+    pub fn load(&self, day: usize, embedded: &'static str) -> Result<Cow<'static, str>, LoadError> {
+        let path = match self {
+            Self::Embedded => return Ok(Cow::Borrowed(embedded)),
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(LoadError::Stdin)?;
+                return Ok(Cow::Owned(input));
+            }
+            Self::Directory(dir) => dir.join(day.to_string()),
+            Self::File(path) => path.clone(),
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(input) => Ok(Cow::Owned(input)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Err(LoadError::Missing { day, path })
+            }
+            Err(err) => Err(LoadError::Io { day, path, err }),
+        }
+    }
+}
+
+/// The names of the input sets in `dir`, ordered by name.
+/// Every subdirectory is a set, laid out like a `Source::Directory`.
+pub fn sets(dir: &Path) -> Result<Vec<String>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Can't read {}: {}", dir.display(), e))?;
+
+    let mut sets = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Can't read {}: {}", dir.display(), e))?;
+        let name = entry.file_name();
+
+        match name.to_str() {
+            Some(name) if entry.path().is_dir() && !name.starts_with('.') => {
+                sets.push(name.to_string())
+            }
+            _ => (),
+        }
+    }
+
+    sets.sort_unstable();
+    Ok(sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn load() {
+        let tmp = TempDir::new("input");
+        let dir = tmp.path().to_path_buf();
+        std::fs::write(dir.join("3"), "..#\n").unwrap();
+
+        assert_eq!(Source::Embedded.load(3, "embedded").unwrap(), "embedded");
+        assert_eq!(
+            Source::Directory(dir.clone()).load(3, "embedded").unwrap(),
+            "..#\n"
+        );
+        assert_eq!(
+            Source::File(dir.join("3")).load(5, "embedded").unwrap(),
+            "..#\n"
+        );
+        assert!(matches!(
+            Source::Directory(dir.clone()).load(4, "embedded"),
+            Err(LoadError::Missing { day: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn named_sets() {
+        let tmp = TempDir::new("sets");
+        let dir = tmp.path();
+        for set in &["bob", "alice", ".hidden"] {
+            std::fs::create_dir_all(dir.join(set)).unwrap();
+        }
+        std::fs::write(dir.join("README"), "not a set").unwrap();
+
+        assert_eq!(sets(dir).unwrap(), vec!["alice", "bob"]);
+        assert!(sets(&dir.join("missing")).is_err());
+    }
+}
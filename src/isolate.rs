@@ -0,0 +1,115 @@
+//! Running solvers in isolation from the rest of a run.
+//!
+//! A job runs on its own thread and reports every finished step. A panic is caught
+//! and reported instead of taking down the whole run, and a caller waiting for a step
+//! can give up after a timeout. Threads can't be killed, so a job which never
+//! finishes keeps running in the background until the program exits.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// What happened to a step of a job
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Done(T),
+    /// The job panicked with the given message
+    Panic(String),
+    /// The step didn't finish in time
+    Timeout,
+}
+
+/// A job running on its own thread
+pub struct Job<T> {
+    rx: Receiver<Result<T, String>>,
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "Unknown panic".to_string(),
+    }
+}
+
+impl<T: Send + 'static> Job<T> {
+    /// Run `job` on a new thread called `name`, `job` reports its steps by calling
+    /// the given function
+    pub fn spawn<F>(name: &str, job: F) -> Self
+    where
+        F: FnOnce(&dyn Fn(T)) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+
+        let spawned = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                // nobody may be waiting anymore after a timeout
+                let step = |step| drop(tx.send(Ok(step)));
+                if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| job(&step))) {
+                    drop(tx.send(Err(panic_message(payload.as_ref()))));
+                }
+            });
+
+        if let Err(e) = spawned {
+            panic!("Can't start a thread for {}: {}", name, e);
+        }
+
+        Self { rx }
+    }
+
+    /// Wait for the next step, at most for `timeout`
+    pub fn next(&self, timeout: Option<Duration>) -> Outcome<T> {
+        let step = match timeout {
+            Some(timeout) => self.rx.recv_timeout(timeout),
+            None => self.rx.recv().map_err(RecvTimeoutError::from),
+        };
+
+        match step {
+            Ok(Ok(step)) => Outcome::Done(step),
+            Ok(Err(message)) => Outcome::Panic(message),
+            Err(RecvTimeoutError::Timeout) => Outcome::Timeout,
+            Err(RecvTimeoutError::Disconnected) => {
+                Outcome::Panic("The job finished without reporting the step".to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps() {
+        let job = Job::spawn("steps", |step| {
+            step(1);
+            step(2);
+            step("x".parse().expect("no third step"));
+        });
+
+        let timeout = Some(Duration::from_secs(10));
+        assert_eq!(job.next(timeout), Outcome::Done(1));
+        assert_eq!(job.next(None), Outcome::Done(2));
+        assert!(matches!(job.next(timeout), Outcome::Panic(m) if m.starts_with("no third step")));
+        assert!(matches!(job.next(timeout), Outcome::Panic(_)));
+    }
+
+    #[test]
+    fn timeout() {
+        let job = Job::spawn("timeout", |step| {
+            step("started");
+            thread::sleep(Duration::from_secs(5));
+            step("never waited for");
+        });
+
+        let timeout = Some(Duration::from_millis(20));
+        assert_eq!(job.next(timeout), Outcome::Done("started"));
+        assert_eq!(job.next(timeout), Outcome::Timeout);
+    }
+}
@@ -0,0 +1,329 @@
+//! Solutions for the Advent of Code 2020 puzzles.
+//!
+//! Every day implements the [`Day`] trait and declares its [`Meta`] data,
+//! all days are listed by [`days`].
+
+pub mod alloc;
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod input;
+pub mod isolate;
+pub mod perf;
+pub mod pool;
+pub mod records;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
+#[cfg(test)]
+mod testutil;
+pub mod watch;
+
+pub use answer::Answer;
+pub use error::SolveError;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The puzzle input of a day.
+///
+/// The raw text is available as `.0`, but days should use the normalised views
+/// `lines`, `blocks` and `grid`. They ignore a byte order mark, accept `\r\n` line
+/// endings, trailing whitespace, trailing blank lines and a missing final newline.
+#[derive(Clone, Debug)]
+pub struct Input<'s>(pub &'s str);
+
+/// Consecutive non-blank lines of an input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block<'s> {
+    /// The number of the first line, starting at 1
+    pub line: usize,
+    pub lines: Vec<&'s str>,
+}
+
+/// A rectangular grid of characters, one row per line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /// The character in column `x` of row `y`, both starting at 0
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        if x < self.width && y < self.height {
+            Some(self.cells[x + y * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.cells.chunks(self.width)
+    }
+}
+
+impl<'s> Input<'s> {
+    /// Every line without line ending and trailing whitespace, blank lines at the end
+    /// of the input are skipped. Line `n` of the input is the `n`th item (starting at 1).
+    pub fn lines(&self) -> impl Iterator<Item = &'s str> {
+        let text = self.0.strip_prefix('\u{feff}').unwrap_or(self.0);
+        let lines: Vec<_> = text.lines().map(str::trim_end).collect();
+        let len = lines.len()
+            - lines
+                .iter()
+                .rev()
+                .take_while(|line| line.is_empty())
+                .count();
+
+        lines.into_iter().take(len)
+    }
+
+    /// The blocks of the input, separated by one or more blank lines
+    pub fn blocks(&self) -> Vec<Block<'s>> {
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut separated = true;
+
+        for (n, line) in self.lines().enumerate() {
+            match blocks.last_mut() {
+                _ if line.is_empty() => separated = true,
+                Some(block) if !separated => block.lines.push(line),
+                _ => {
+                    blocks.push(Block {
+                        line: n + 1,
+                        lines: vec![line],
+                    });
+                    separated = false;
+                }
+            }
+        }
+
+        blocks
+    }
+
+    /// The input as a grid, every line has to be as wide as the first one
+    pub fn grid(&self) -> Result<Grid, SolveError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (n, line) in self.lines().enumerate() {
+            match width {
+                Some(width) if width != line.len() => {
+                    return Err(SolveError::parse(
+                        n + 1,
+                        1,
+                        format!("Expected {} characters like the first line", width),
+                    ))
+                }
+                _ => width = Some(line.len()),
+            }
+            cells.extend_from_slice(line.as_bytes());
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(SolveError::invalid("The grid is empty")),
+        }
+    }
+
+    /// Parse every non-empty line of the input as `T`
+    pub fn parse_lines<T>(&self) -> Result<Vec<T>, SolveError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                line.parse()
+                    .map_err(|e| SolveError::parse(n + 1, 1, format!("'{}': {}", line, e)))
+            })
+            .collect()
+    }
+}
+
+/// A puzzle solver, solvers may be run on several threads at once.
+///
+/// The input is parsed once by `parse`, the parsed input is shared by both parts.
+pub trait Day: Send + Sync {
+    type Parsed<'i>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError>;
+
+    fn part1(&self, _parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+    fn part2(&self, _parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+}
+
+/// The parsed input of a day, ready to be solved
+pub trait Parsed {
+    fn solve(&self, part: usize) -> Result<Answer, SolveError>;
+}
+
+/// The object safe counterpart of `Day`, implemented for every `Day`
+pub trait Solver: Send + Sync {
+    fn parse<'a>(&'a self, input: &Input<'a>) -> Result<Box<dyn Parsed + 'a>, SolveError>;
+}
+
+struct ParsedDay<'a, D: Day> {
+    day: &'a D,
+    parsed: D::Parsed<'a>,
+}
+
+impl<D: Day> Parsed for ParsedDay<'_, D> {
+    fn solve(&self, part: usize) -> Result<Answer, SolveError> {
+        match part {
+            1 => self.day.part1(&self.parsed),
+            2 => self.day.part2(&self.parsed),
+            _ => Err(SolveError::invalid(format!("There is no part {}", part))),
+        }
+    }
+}
+
+impl<D: Day> Solver for D {
+    fn parse<'a>(&'a self, input: &Input<'a>) -> Result<Box<dyn Parsed + 'a>, SolveError> {
+        let parsed = Day::parse(self, input)?;
+        Ok(Box::new(ParsedDay { day: self, parsed }))
+    }
+}
+
+/// Declared by every day module as `pub const META: Meta`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Meta {
+    pub day: usize,
+    pub title: &'static str,
+    /// The input embedded in the library
+    pub input: &'static str,
+}
+
+/// A solver together with the metadata of its day
+pub struct Entry {
+    pub day: usize,
+    pub title: &'static str,
+    pub input: &'static str,
+    /// Shared with the threads the solver is run on
+    pub solver: Arc<dyn Solver>,
+}
+
+impl Entry {
+    pub fn new(meta: Meta, solver: Box<dyn Solver>) -> Self {
+        Self {
+            day: meta.day,
+            title: meta.title,
+            input: meta.input,
+            solver: solver.into(),
+        }
+    }
+}
+
+/// Declare the day modules and collect them in `days()`.
+/// Every module has to provide `Day` and `META`.
+macro_rules! days {
+    ($($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// All available days, ordered by day
+        pub fn days() -> Vec<Entry> {
+            let mut days = vec![$(Entry::new($module::META, Box::new($module::Day))),*];
+            days.sort_by_key(|entry| entry.day);
+            days
+        }
+    };
+}
+
+days! {
+    day1,
+    day2,
+    day3,
+    day4,
+    day5,
+    day6,
+    day7,
+    day8,
+    day9,
+}
+
+/// The day with the given number
+pub fn day(day: usize) -> Option<Entry> {
+    days().into_iter().find(|entry| entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_days() {
+        let days: Vec<_> = days().iter().map(|entry| entry.day).collect();
+        let mut unique = days.clone();
+        unique.dedup();
+
+        assert_eq!(days, unique);
+    }
+
+    #[test]
+    fn normalised_input() {
+        let unix = Input("ab\n\ncd \nef\n\n\ngh\n\n");
+        let dos = Input("\u{feff}ab\r\n\r\ncd \r\nef\r\n\r\n\r\ngh");
+
+        for input in &[unix, dos] {
+            assert_eq!(
+                input.lines().collect::<Vec<_>>(),
+                vec!["ab", "", "cd", "ef", "", "", "gh"]
+            );
+            assert_eq!(
+                input.blocks(),
+                vec![
+                    Block {
+                        line: 1,
+                        lines: vec!["ab"],
+                    },
+                    Block {
+                        line: 3,
+                        lines: vec!["cd", "ef"],
+                    },
+                    Block {
+                        line: 7,
+                        lines: vec!["gh"],
+                    },
+                ]
+            );
+        }
+
+        assert_eq!(Input("").lines().count(), 0);
+        assert_eq!(Input("\n\n").blocks(), vec![]);
+    }
+
+    #[test]
+    fn grid() {
+        let grid = Input("\u{feff}#..\r\n.#.\r\n\r\n").grid().unwrap();
+        assert_eq!((grid.width, grid.height), (3, 2));
+        assert_eq!(grid.get(1, 1), Some(b'#'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.rows().nth(1), Some(&b".#."[..]));
+
+        assert_eq!(
+            Input("#..\n.#\n").grid(),
+            Err(SolveError::parse(
+                2,
+                1,
+                "Expected 3 characters like the first line"
+            ))
+        );
+        assert!(Input("\n").grid().is_err());
+    }
+}
@@ -1,60 +1,875 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+mod cli;
 
-#[derive(Clone, Debug)]
-struct Input<'s>(&'s str);
+use aoc_rs::answers::Answers;
+use aoc_rs::isolate::{self, Outcome};
+use aoc_rs::perf::{self, Measurement, Step};
+use aoc_rs::report::{Format, Record, Report, Status};
+use aoc_rs::submit::{Attempt, History, Verdict};
+use aoc_rs::{alloc, bench, examples, fetch, input, pool, scaffold, submit, watch};
+use aoc_rs::{day8, Answer, Day, Entry, Input, Solver};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-trait Day {
-    fn part1(&self, _input: &Input) -> String {
-        String::new()
+fn timed<F, T>(func: F) -> (T, Duration)
+where
+    F: Fn() -> T,
+{
+    let start = Instant::now();
+    let result = func();
+    (result, start.elapsed())
+}
+
+/// Load the input of every selected day from `source`
+fn load_inputs<'a>(
+    days: &'a [Entry],
+    selected: &'a [usize],
+    source: &'a input::Source,
+) -> impl Iterator<
+    Item = (
+        usize,
+        &'a Arc<dyn Solver>,
+        Result<Cow<'static, str>, String>,
+    ),
+> + 'a {
+    selected.iter().map(move |&i| {
+        // the selected days have been checked by the command line parser
+        let entry = days.iter().find(|entry| entry.day == i).unwrap();
+        let input = source.load(i, entry.input).map_err(|e| e.to_string());
+
+        (i, &entry.solver, input)
+    })
+}
+
+/// Exit after a failed write to stdout, quietly if the reader went away (e.g. `| head`)
+fn stdout_failed<T>(e: io::Error) -> T {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        std::process::exit(0);
     }
-    fn part2(&self, _input: &Input) -> String {
-        String::new()
+    eprintln!("error: Can't write to stdout: {}", e);
+    std::process::exit(1);
+}
+
+/// The input sets to run with their source, `None` is the set given by the source
+fn input_sets(selection: &cli::Selection) -> Result<Vec<(Option<String>, input::Source)>, String> {
+    let dir = std::env::var_os(input::SETS_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"));
+
+    let names = match &selection.sets {
+        cli::Sets::Default => return Ok(vec![(None, with_inputs_env(selection.source.clone()))]),
+        cli::Sets::Named(names) => names.clone(),
+        cli::Sets::All => match input::sets(&dir)? {
+            sets if sets.is_empty() => return Err(format!("No input sets in {}", dir.display())),
+            sets => sets,
+        },
+    };
+
+    names
+        .into_iter()
+        .map(|name| match dir.join(&name) {
+            set if set.is_dir() => Ok((Some(name), input::Source::Directory(set))),
+            set => Err(format!(
+                "Unknown input set '{}' (expected {})",
+                name,
+                set.display()
+            )),
+        })
+        .collect()
+}
+
+/// A finished step of a day running in isolation
+enum Progress {
+    Parsed(Result<(), String>, Duration, Option<alloc::Stats>),
+    Solved(Result<Answer, String>, Duration, Option<alloc::Stats>),
+}
+
+/// Parse `input` and solve `parts` on a thread of their own
+fn spawn_day(
+    i: usize,
+    solver: &Arc<dyn Solver>,
+    input: &Arc<str>,
+    parts: &[usize],
+) -> isolate::Job<Progress> {
+    let (solver, input, parts) = (Arc::clone(solver), Arc::clone(input), parts.to_vec());
+
+    isolate::Job::spawn(&format!("day{:0>2}", i), move |step| {
+        let input = Input(&input);
+        let ((parsed, elapsed), stats) = alloc::measure(|| timed(|| solver.parse(&input)));
+        let parsed = match parsed {
+            Ok(parsed) => {
+                step(Progress::Parsed(Ok(()), elapsed, stats));
+                parsed
+            }
+            Err(e) => return step(Progress::Parsed(Err(e.to_string()), elapsed, stats)),
+        };
+
+        for part in parts {
+            let ((answer, elapsed), stats) = alloc::measure(|| timed(|| parsed.solve(part)));
+            step(Progress::Solved(
+                answer.map_err(|e| e.to_string()),
+                elapsed,
+                stats,
+            ));
+        }
+    })
+}
+
+/// Parse the input of a day once, then run the selected parts and record their outcome.
+///
+/// Every step has to finish within `timeout`. The remaining parts are run on a
+/// fresh parse of the input after a part panicked or timed out.
+fn run_day(
+    (i, solver, input): (usize, &Arc<dyn Solver>, &Result<Cow<'static, str>, String>),
+    parts: &[usize],
+    set: Option<&str>,
+    answers: Option<&Answers>,
+    timeout: Option<Duration>,
+) -> Vec<Record> {
+    let mut records: Vec<_> = parts
+        .iter()
+        .map(|&part| Record {
+            set: set.map(String::from),
+            day: i,
+            part,
+            answer: None,
+            expected: answers.and_then(|a| a.get(i, part)).cloned(),
+            parse_elapsed: None,
+            elapsed: None,
+            parse_alloc: None,
+            alloc: None,
+            status: Status::Error,
+            error: None,
+        })
+        .collect();
+
+    let input: Arc<str> = match input {
+        Ok(input) => Arc::from(input.as_ref()),
+        Err(e) => {
+            records.iter_mut().for_each(|r| r.error = Some(e.clone()));
+            return records;
+        }
+    };
+
+    let gave_up = |timeout: Option<Duration>| {
+        format!(
+            "Gave up after {} s",
+            timeout.unwrap_or_default().as_secs_f64()
+        )
+    };
+
+    // the index of the next part to run
+    let mut next = 0;
+    while next < records.len() {
+        let job = spawn_day(i, solver, &input, &parts[next..]);
+
+        let failure = match job.next(timeout) {
+            Outcome::Done(Progress::Parsed(parsed, elapsed, stats)) => {
+                // only the first parse is reported
+                if next == 0 {
+                    records.iter_mut().for_each(|r| {
+                        r.parse_elapsed = Some(elapsed);
+                        r.parse_alloc = stats;
+                    });
+                }
+                parsed.err().map(|e| (Status::Error, e))
+            }
+            Outcome::Done(Progress::Solved(..)) => unreachable!("the input is parsed first"),
+            Outcome::Panic(message) => Some((Status::Panic, format!("Parsing: {}", message))),
+            Outcome::Timeout => Some((Status::Timeout, format!("Parsing: {}", gave_up(timeout)))),
+        };
+
+        if let Some((status, error)) = failure {
+            records[next..].iter_mut().for_each(|r| {
+                r.status = status;
+                r.error = Some(error.clone());
+            });
+            break;
+        }
+
+        while let Some(record) = records.get_mut(next) {
+            next += 1;
+
+            match job.next(timeout) {
+                Outcome::Done(Progress::Solved(answer, elapsed, stats)) => {
+                    record.elapsed = Some(elapsed);
+                    record.alloc = stats;
+
+                    match answer {
+                        Ok(answer) => {
+                            record.status = match answers {
+                                _ if !answer.is_solved() => Status::Unsolved,
+                                Some(answers) => answers.check(i, record.part, &answer).into(),
+                                None => Status::Ok,
+                            };
+                            record.answer = Some(answer);
+                        }
+                        Err(e) => record.error = Some(e),
+                    }
+                }
+                Outcome::Done(Progress::Parsed(..)) => unreachable!("the input is parsed once"),
+                Outcome::Panic(message) => {
+                    record.status = Status::Panic;
+                    record.error = Some(message);
+                    break;
+                }
+                Outcome::Timeout => {
+                    record.status = Status::Timeout;
+                    record.error = Some(gave_up(timeout));
+                    break;
+                }
+            }
+        }
     }
+
+    records
 }
 
-fn timed<F>(day: usize, part: usize, func: F)
-where
-    F: Fn() -> String,
-{
-    let start = std::time::Instant::now();
-    let result = func();
-    let elapsed = start.elapsed();
+/// Run (and optionally verify) the selected days on every selected input set,
+/// returns false on any failure
+fn run(days: &[Entry], selection: &cli::Selection, verify: bool) -> bool {
+    let sets = match input_sets(selection) {
+        Ok(sets) => sets,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return false;
+        }
+    };
+
+    let mut report =
+        Report::new(selection.format, std::io::stdout().lock()).unwrap_or_else(stdout_failed);
+    let mut success = true;
+
+    let jobs = match selection.jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        jobs => jobs,
+    };
+
+    for (set, source) in sets {
+        // every set is verified against the manifest next to its inputs
+        let answers = if verify {
+            match Answers::load(&source, selection.answers.as_deref()) {
+                Ok(answers) => Some(answers),
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    success = false;
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        if let Some(set) = &set {
+            report.begin_set(set).unwrap_or_else(stdout_failed);
+        }
+
+        let inputs: Vec<_> = load_inputs(days, &selection.days, &source).collect();
+        let tasks: Vec<_> = inputs
+            .iter()
+            .map(|(i, solver, input)| (*i, *solver, input))
+            .collect();
+
+        pool::run_ordered(
+            jobs,
+            tasks,
+            |task| {
+                run_day(
+                    task,
+                    &selection.parts,
+                    set.as_deref(),
+                    answers.as_ref(),
+                    selection.timeout,
+                )
+            },
+            |records| {
+                for record in records {
+                    success &= !matches!(
+                        record.status,
+                        Status::Fail | Status::Error | Status::Panic | Status::Timeout
+                    );
+                    report.push(record).unwrap_or_else(stdout_failed);
+                }
+                report.end_day().unwrap_or_else(stdout_failed);
+            },
+        );
+    }
+
+    report.finish().unwrap_or_else(stdout_failed);
+    success
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+fn us(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+/// Benchmark the selected days and append the results to the `record` history,
+/// returns false on any failure
+fn bench(
+    days: &[Entry],
+    selection: &cli::Selection,
+    config: &bench::Config,
+    record: Option<&Path>,
+) -> bool {
+    let mut success = true;
+    let mut measurements = Vec::new();
+    let (time, commit) = match record {
+        Some(_) => (
+            unix_time(),
+            perf::commit(Path::new(env!("CARGO_MANIFEST_DIR"))),
+        ),
+        None => (0, String::new()),
+    };
 
     println!(
-        "day{:0>2}-part{} {:>9} us {:>12}",
-        day,
-        part,
-        elapsed.as_micros(),
-        result
+        "{:11} {:>7} {:>11} {:>11} {:>11} {:>11} {:>11}",
+        "(us)", "runs", "min", "median", "mean", "stddev", "p95"
     );
+
+    let mut print = |day: usize, step: Step, samples: &[Duration]| {
+        if let Some(stats) = bench::Stats::new(samples) {
+            println!(
+                "{:11} {:>7} {:>11.1} {:>11.1} {:>11.1} {:>11.1} {:>11.1}",
+                format!("day{:0>2}-{}", day, step),
+                stats.runs,
+                us(stats.min),
+                us(stats.median),
+                us(stats.mean),
+                us(stats.stddev),
+                us(stats.p95)
+            );
+            measurements.push(Measurement::new(time, &commit, day, step, &stats));
+        }
+    };
+
+    for (i, solver, input) in load_inputs(days, &selection.days, &selection.source) {
+        let input = match &input {
+            Ok(input) => Input(input),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                success = false;
+                continue;
+            }
+        };
+
+        // don't benchmark days which fail to parse anyway
+        let parsed = match solver.parse(&input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("error: day{:0>2}-parse: {}", i, e);
+                success = false;
+                continue;
+            }
+        };
+
+        print(
+            i,
+            Step::Parse,
+            &bench::measure(config, || solver.parse(&input)),
+        );
+
+        for &part in &selection.parts {
+            if let Err(e) = parsed.solve(part) {
+                eprintln!("error: day{:0>2}-part{}: {}", i, part, e);
+                success = false;
+                continue;
+            }
+
+            print(
+                i,
+                Step::Part(part),
+                &bench::measure(config, || parsed.solve(part)),
+            );
+        }
+        println!();
+    }
+
+    if let Some(path) = record {
+        match perf::History::record(path, &measurements) {
+            Ok(()) => println!("Recorded {} on {} in {}", time, commit, path.display()),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                success = false;
+            }
+        }
+    }
+
+    success
 }
 
-fn main() {
-    let days: Vec<(Box<dyn Day>, _)> = vec![
-        (Box::new(day1::Day), Input(include_str!("inputs/1"))),
-        (Box::new(day2::Day), Input(include_str!("inputs/2"))),
-        (Box::new(day3::Day), Input(include_str!("inputs/3"))),
-        (Box::new(day4::Day), Input(include_str!("inputs/4"))),
-        (Box::new(day5::Day), Input(include_str!("inputs/5"))),
-        (Box::new(day6::Day), Input(include_str!("inputs/6"))),
-        (Box::new(day7::Day), Input(include_str!("inputs/7"))),
-        (Box::new(day8::Day), Input(include_str!("inputs/8"))),
-        (Box::new(day9::Day), Input(include_str!("inputs/9"))),
-    ];
-
-    for (mut i, (day, input)) in days.iter().enumerate() {
-        i += 1;
-
-        timed(i, 1, || day.part1(&input));
-        timed(i, 2, || day.part2(&input));
+/// Compare the latest recorded benchmark of `days` (all if empty) with the baseline,
+/// returns false if any part got slower than `threshold` percent
+fn compare(days: &[usize], baseline: Option<&str>, threshold: usize, history: &Path) -> bool {
+    let history = match perf::History::load(history) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return false;
+        }
+    };
+    let (baseline, current) = match history.baseline(baseline) {
+        Ok(runs) => runs,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return false;
+        }
+    };
+
+    let selected = |(time, commit): (u64, &str)| -> Vec<&Measurement> {
+        history
+            .run(time, commit)
+            .into_iter()
+            .filter(|m| days.is_empty() || days.contains(&m.day))
+            .collect()
+    };
+    let (baseline, current) = (selected(baseline), selected(current));
+
+    if let (Some(base), Some(cur)) = (baseline.first(), current.first()) {
+        println!(
+            "Comparing {} ({}) with the baseline {} ({})\n",
+            cur.commit, cur.time, base.commit, base.time
+        );
+    }
+
+    println!(
+        "{:11} {:>11} {:>11} {:>9}",
+        "(us)", "baseline", "current", "change"
+    );
+
+    let mut success = true;
+    for comparison in perf::compare(&baseline, &current, threshold as f64) {
+        let change = match comparison.change() {
+            Some(change) => format!("{:+.1}%", change),
+            None => "n/a".to_string(),
+        };
+        println!(
+            "day{:0>2}-{:5} {:>11.1} {:>11.1} {:>9}{}",
+            comparison.day,
+            comparison.step,
+            us(comparison.baseline),
+            us(comparison.current),
+            change,
+            if comparison.regression { " SLOWER" } else { "" }
+        );
+        success &= !comparison.regression;
+    }
+
+    success
+}
+
+/// The inputs directory of the crate the binary was built from
+fn crate_inputs() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/inputs")
+}
+
+fn base_url(url: Option<String>) -> String {
+    url.or_else(|| std::env::var(fetch::URL_ENV).ok())
+        .unwrap_or_else(|| fetch::DEFAULT_URL.to_string())
+}
+
+/// Solve a part and submit the answer unless the history tells it's wrong
+fn submit(
+    entry: &Entry,
+    part: usize,
+    source: &input::Source,
+    url: &str,
+    history_path: &Path,
+) -> Result<Verdict, String> {
+    let input = source
+        .load(entry.day, entry.input)
+        .map_err(|e| e.to_string())?;
+    let answer = entry
+        .solver
+        .parse(&Input(&input))
+        .and_then(|parsed| parsed.solve(part))
+        .map_err(|e| format!("day{:0>2}-part{}: {}", entry.day, part, e))?;
+
+    let mut history = History::load(history_path)?;
+    let now = unix_time();
+
+    if let Some(reason) = history.refuse(entry.day, part, &answer, now) {
+        return Err(format!("Not submitting: {}", reason));
+    }
+
+    let client = fetch::Client::new(url, &fetch::session().map_err(|e| e.to_string())?);
+    let page = client
+        .submit(entry.day, part, &answer.to_string())
+        .map_err(|e| e.to_string())?;
+    let verdict = Verdict::from_response(&page);
+
+    println!(
+        "day{:0>2}-part{} {:>12} {}",
+        entry.day, part, answer, verdict
+    );
+
+    history.record(
+        history_path,
+        Attempt {
+            time: now,
+            day: entry.day,
+            part,
+            answer,
+            verdict,
+        },
+    )?;
+
+    Ok(verdict)
+}
+
+/// Run, verify and check the examples of a day once, prints how the answers differ
+/// from `previous` and updates it
+fn watch_once(
+    entry: &Entry,
+    parts: &[usize],
+    source: &input::Source,
+    answers: Option<&Path>,
+    examples: Option<&Path>,
+    timeout: Option<Duration>,
+    previous: &mut HashMap<usize, Answer>,
+) {
+    // the manifest may have changed as well
+    let answers = match Answers::load(source, answers) {
+        Ok(answers) => Some(answers),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            None
+        }
+    };
+
+    let input = source
+        .load(entry.day, entry.input)
+        .map_err(|e| e.to_string());
+    let records = run_day(
+        (entry.day, &entry.solver, &input),
+        parts,
+        None,
+        answers.as_ref(),
+        timeout,
+    );
+
+    let mut report =
+        Report::new(Format::Text, std::io::stdout().lock()).unwrap_or_else(stdout_failed);
+    let mut changes = Vec::new();
+    for record in records {
+        if let Some(answer) = &record.answer {
+            if let Some(old) = previous.insert(record.part, answer.clone()) {
+                changes.push((record.part, watch::diff(&old, answer)));
+            }
+        }
+        report.push(record).unwrap_or_else(stdout_failed);
+    }
+    report.end_day().unwrap_or_else(stdout_failed);
+    report.finish().unwrap_or_else(stdout_failed);
+
+    for (part, diff) in changes.into_iter().filter(|(_, diff)| !diff.is_empty()) {
+        println!("day{:0>2}-part{} changed", entry.day, part);
+        for line in diff {
+            println!("    {}", line);
+        }
+    }
+
+    if let Some(dir) = examples {
+        match examples::load(dir, entry.day) {
+            Ok(examples) => {
+                for example in examples {
+                    match examples::check(entry, &example) {
+                        Ok(()) => println!("day{:0>2} example '{}' PASS", entry.day, example.name),
+                        Err(e) => println!("{}", e),
+                    }
+                }
+            }
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+}
+
+/// Run a day whenever its input or examples change, never returns
+fn watch(
+    entry: &Entry,
+    parts: &[usize],
+    source: &input::Source,
+    answers: Option<&Path>,
+    examples: bool,
+    interval: Duration,
+    timeout: Option<Duration>,
+) -> ! {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let examples = if examples {
+        Some(root.join("src/examples"))
+    } else {
+        None
+    };
+    let code = root.join(format!("src/day{}.rs", entry.day));
+
+    let mut paths = vec![code.clone()];
+    paths.extend(match source {
+        input::Source::Directory(dir) => Some(dir.join(entry.day.to_string())),
+        input::Source::File(path) => Some(path.clone()),
+        _ => None,
+    });
+    paths.extend(answers.map(PathBuf::from));
+    paths.extend(
+        examples
+            .as_deref()
+            .map(|dir| examples::path(dir, entry.day)),
+    );
+
+    let mut watcher = watch::Watcher::new(paths);
+    let mut previous = HashMap::new();
+
+    println!("Watching day {}, press Ctrl-C to stop\n", entry.day);
+    watch_once(
+        entry,
+        parts,
+        source,
+        answers,
+        examples.as_deref(),
+        timeout,
+        &mut previous,
+    );
+
+    loop {
+        std::thread::sleep(interval);
+
+        let changed = watcher.changed();
+        if changed.is_empty() {
+            continue;
+        }
+
+        println!();
+        for path in &changed {
+            println!("{} changed", path.display());
+        }
+
+        // a running binary can't pick up changes of the solver
+        if changed == [code.as_path()] {
+            println!("Rebuild to run the changed solver");
+            continue;
+        }
         println!();
+
+        watch_once(
+            entry,
+            parts,
+            source,
+            answers,
+            examples.as_deref(),
+            timeout,
+            &mut previous,
+        );
+    }
+}
+
+/// Load the day 8 program from `source`
+fn day8_program(source: &input::Source) -> Result<Vec<day8::Instruction>, String> {
+    let input = source
+        .load(day8::META.day, day8::META.input)
+        .map_err(|e| e.to_string())?;
+    Day::parse(&day8::Day, &Input(&input)).map_err(|e| e.to_string())
+}
+
+/// Debug the day 8 program loaded from `source` on stdin and stdout
+fn debug(source: &input::Source) -> Result<(), String> {
+    let program = day8_program(source)?;
+
+    day8::debugger::repl(&program, std::io::stdin().lock(), std::io::stdout().lock())
+        .map_err(|e| e.to_string())
+}
+
+/// Print the trace of the day 8 program loaded from `source`, or its statistics
+fn trace(source: &input::Source, format: Format, stats: bool, loops: usize) -> Result<(), String> {
+    let trace = day8::trace::Trace::run(&day8_program(source)?, loops);
+
+    match (stats, format) {
+        (true, _) => print!("{}", trace.stats()),
+        (false, Format::Json) => print!("{}", trace.to_json()),
+        (false, _) => print!("{}", trace.to_text()),
+    }
+    Ok(())
+}
+
+/// Print the lints of the day 8 program loaded from `source`, or its control-flow
+/// graph, whether the program is free of lints
+fn lint(source: &input::Source, dot: bool) -> Result<bool, String> {
+    let program = day8_program(source)?;
+    let cfg = day8::cfg::Cfg::new(&program);
+
+    if dot {
+        print!("{}", cfg.to_dot());
+        return Ok(true);
+    }
+
+    let lints = cfg.lints();
+    for lint in &lints {
+        println!("{}", lint);
+    }
+    Ok(lints.is_empty())
+}
+
+fn main() {
+    let days = aoc_rs::days();
+
+    let available: Vec<_> = days.iter().map(|entry| entry.day).collect();
+
+    let success = match cli::parse(std::env::args().skip(1), &available) {
+        Err(e) => {
+            eprintln!("error: {}\nTry 'aoc-rs help' for the usage", e);
+            std::process::exit(2);
+        }
+        Ok(cli::Command::Help) => {
+            println!("{}", cli::USAGE);
+            true
+        }
+        Ok(cli::Command::List) => {
+            for entry in &days {
+                println!("day{:0>2} {}", entry.day, entry.title);
+            }
+            true
+        }
+        Ok(cli::Command::Run(selection)) => run(&days, &selection, false),
+        Ok(cli::Command::Verify(selection)) => run(&days, &selection, true),
+        Ok(cli::Command::Bench(mut selection, config, record)) => {
+            selection.source = with_inputs_env(selection.source);
+            bench(&days, &selection, &config, record.as_deref())
+        }
+        Ok(cli::Command::Compare {
+            days,
+            baseline,
+            threshold,
+            history,
+        }) => compare(&days, baseline.as_deref(), threshold, &history),
+        Ok(cli::Command::New { day, title }) => {
+            // the sources are only available in the crate the binary was built from
+            match scaffold::create(Path::new(env!("CARGO_MANIFEST_DIR")), day, &title) {
+                Ok(created) => {
+                    for path in created {
+                        println!("{}", path.display());
+                    }
+                    true
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    false
+                }
+            }
+        }
+        Ok(cli::Command::Fetch { day, inputs, url }) => {
+            let inputs = inputs
+                .or_else(|| std::env::var_os(input::INPUTS_ENV).map(Into::into))
+                .unwrap_or_else(crate_inputs);
+            let url = base_url(url);
+
+            let client = || Ok(fetch::Client::new(&url, &fetch::session()?));
+
+            match fetch::fetch(&inputs, day, client) {
+                Ok((path, downloaded)) => {
+                    let state = if downloaded { "downloaded" } else { "cached" };
+                    println!("{} ({})", path.display(), state);
+                    true
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    false
+                }
+            }
+        }
+        Ok(cli::Command::Submit {
+            day,
+            part,
+            source,
+            url,
+            history,
+        }) => {
+            let source = with_inputs_env(source);
+            let history = history.unwrap_or_else(|| match &source {
+                input::Source::Directory(dir) => dir.join(submit::HISTORY),
+                _ => Path::new(env!("CARGO_MANIFEST_DIR")).join(submit::CRATE_HISTORY),
+            });
+            // the selected day has been checked by the command line parser
+            let entry = days.iter().find(|entry| entry.day == day).unwrap();
+
+            match submit(entry, part, &source, &base_url(url), &history) {
+                Ok(Verdict::Correct) => true,
+                Ok(_) => false,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    false
+                }
+            }
+        }
+        Ok(cli::Command::Watch {
+            day,
+            parts,
+            source,
+            answers,
+            examples,
+            interval,
+            timeout,
+        }) => {
+            // the embedded inputs never change
+            let source = match with_inputs_env(source) {
+                input::Source::Embedded => input::Source::Directory(crate_inputs()),
+                source => source,
+            };
+            // the selected day has been checked by the command line parser
+            let entry = days.iter().find(|entry| entry.day == day).unwrap();
+
+            watch(
+                entry,
+                &parts,
+                &source,
+                answers.as_deref(),
+                examples,
+                interval,
+                timeout,
+            )
+        }
+        Ok(cli::Command::Debug(source)) => match debug(&with_inputs_env(source)) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                false
+            }
+        },
+        Ok(cli::Command::Trace {
+            source,
+            format,
+            stats,
+            loops,
+        }) => match trace(&with_inputs_env(source), format, stats, loops) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                false
+            }
+        },
+        Ok(cli::Command::Lint { source, dot }) => match lint(&with_inputs_env(source), dot) {
+            Ok(clean) => clean,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                false
+            }
+        },
+    };
+
+    if !success {
+        std::process::exit(1);
+    }
+}
+
+/// Use the inputs directory from the environment if no inputs were given
+fn with_inputs_env(source: input::Source) -> input::Source {
+    match (source, std::env::var_os(input::INPUTS_ENV)) {
+        (input::Source::Embedded, Some(dir)) => input::Source::Directory(dir.into()),
+        (source, _) => source,
     }
 }
@@ -0,0 +1,414 @@
+use crate::bench::Stats;
+use crate::records;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Name of the benchmark history in the crate directory
+pub const HISTORY: &str = "bench-history";
+
+/// What was measured of a day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Step {
+    Parse,
+    Part(usize),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse => f.pad("parse"),
+            Self::Part(part) => f.pad(&format!("part{}", part)),
+        }
+    }
+}
+
+impl FromStr for Step {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parse" => Ok(Self::Parse),
+            _ => s
+                .strip_prefix("part")
+                .and_then(|part| part.parse().ok())
+                .map(Self::Part)
+                .ok_or_else(|| format!("Invalid step '{}'", s)),
+        }
+    }
+}
+
+/// The result of benchmarking one step of a day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    /// When the benchmark was run, in seconds since the unix epoch
+    pub time: u64,
+    /// The commit the benchmark was run on, see `commit`
+    pub commit: String,
+    pub day: usize,
+    pub step: Step,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Measurement {
+    pub fn new(time: u64, commit: &str, day: usize, step: Step, stats: &Stats) -> Self {
+        Self {
+            time,
+            commit: commit.to_string(),
+            day,
+            step,
+            runs: stats.runs,
+            min: stats.min,
+            median: stats.median,
+            mean: stats.mean,
+        }
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {}",
+            self.time,
+            self.commit,
+            self.day,
+            self.step,
+            self.runs,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos()
+        )
+    }
+}
+
+/// A recorded run, identified by its time and commit
+pub type Run<'h> = (u64, &'h str);
+
+/// Every recorded benchmark run.
+///
+/// The history is a record file (see [`records`]) with one
+/// `<time> <commit> <day> <step> <runs> <min> <median> <mean>` record per measurement,
+/// durations are in nanoseconds. All measurements of a run share the same time and
+/// commit, a run is identified by both and measures every step only once.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History(Vec<Measurement>);
+
+impl FromStr for History {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut measurements = Vec::new();
+        let mut measured = HashSet::new();
+
+        for (n, line) in records::records(s) {
+            let invalid = || {
+                format!(
+                    "line {}: expected '<time> <commit> <day> <step> <runs> <min> <median> <mean>'",
+                    n
+                )
+            };
+            let nanos = |s: &str| s.parse().map(Duration::from_nanos).map_err(|_| invalid());
+
+            let measurement = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [time, commit, day, step, runs, min, median, mean] => Measurement {
+                    time: time.parse().map_err(|_| invalid())?,
+                    commit: commit.to_string(),
+                    day: day.parse().map_err(|_| invalid())?,
+                    step: step.parse().map_err(|e| format!("line {}: {}", n, e))?,
+                    runs: runs.parse().map_err(|_| invalid())?,
+                    min: nanos(min)?,
+                    median: nanos(median)?,
+                    mean: nanos(mean)?,
+                },
+                _ => return Err(invalid()),
+            };
+
+            let key = (
+                measurement.time,
+                measurement.commit.clone(),
+                measurement.day,
+                measurement.step,
+            );
+            if !measured.insert(key) {
+                return Err(format!(
+                    "line {}: day {} {} is measured twice in the run {} on {}",
+                    n, measurement.day, measurement.step, measurement.time, measurement.commit
+                ));
+            }
+            measurements.push(measurement);
+        }
+
+        Ok(History(measurements))
+    }
+}
+
+impl History {
+    /// Load the history from `path`, a missing file is an empty history
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(records::load(path)?.unwrap_or_default())
+    }
+
+    /// Append the measurements of a run to the file at `path`, a run at the same time
+    /// on the same commit must not be recorded already
+    pub fn record(path: &Path, measurements: &[Measurement]) -> Result<(), String> {
+        if let Some(first) = measurements.first() {
+            if !Self::load(path)?.run(first.time, &first.commit).is_empty() {
+                return Err(format!(
+                    "A run {} on {} is recorded in {} already",
+                    first.time,
+                    first.commit,
+                    path.display()
+                ));
+            }
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| {
+                measurements
+                    .iter()
+                    .try_for_each(|measurement| writeln!(file, "{}", measurement))
+            })
+            .map_err(|e| format!("Can't write {}: {}", path.display(), e))
+    }
+
+    /// The recorded runs as (time, commit), oldest first
+    pub fn runs(&self) -> Vec<Run<'_>> {
+        let mut runs: Vec<_> = self.0.iter().map(|m| (m.time, m.commit.as_str())).collect();
+        runs.sort_unstable();
+        runs.dedup();
+        runs
+    }
+
+    /// The measurements of the run at `time` on `commit`
+    pub fn run(&self, time: u64, commit: &str) -> Vec<&Measurement> {
+        self.0
+            .iter()
+            .filter(|m| m.time == time && m.commit == commit)
+            .collect()
+    }
+
+    /// The latest run and the run to compare it with: the latest earlier run on a commit
+    /// starting with `baseline`, or the run before the latest one
+    pub fn baseline(&self, baseline: Option<&str>) -> Result<(Run<'_>, Run<'_>), String> {
+        let runs = self.runs();
+        let (current, earlier) = match runs.split_last() {
+            Some((current, earlier)) if !earlier.is_empty() => (current, earlier),
+            _ => return Err("At least two recorded runs are needed".to_string()),
+        };
+
+        let base = match baseline {
+            None => earlier.last(),
+            Some(commit) => earlier
+                .iter()
+                .rev()
+                .find(|(_, run)| run.starts_with(commit)),
+        };
+
+        match base {
+            Some(base) => Ok((*base, *current)),
+            None => Err(format!(
+                "There is no run on commit {} before the latest one",
+                baseline.unwrap_or_default()
+            )),
+        }
+    }
+}
+
+/// A step measured in both runs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub day: usize,
+    pub step: Step,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Slower than the baseline by more than the threshold
+    pub regression: bool,
+}
+
+impl Comparison {
+    /// The change of the median in percent, positive if slower, `None` if the
+    /// baseline took no measurable time
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline.as_secs_f64();
+        if baseline == 0.0 {
+            return None;
+        }
+        Some((self.current.as_secs_f64() - baseline) / baseline * 100.0)
+    }
+}
+
+/// Compare the medians of the steps measured in both runs, ordered by day and step.
+/// Steps more than `threshold` percent slower than the baseline are regressions.
+pub fn compare(
+    baseline: &[&Measurement],
+    current: &[&Measurement],
+    threshold: f64,
+) -> Vec<Comparison> {
+    let mut comparisons: Vec<_> = current
+        .iter()
+        .filter_map(|current| {
+            let baseline = baseline
+                .iter()
+                .find(|base| base.day == current.day && base.step == current.step)?;
+
+            let mut comparison = Comparison {
+                day: current.day,
+                step: current.step,
+                baseline: baseline.median,
+                current: current.median,
+                regression: false,
+            };
+            comparison.regression = comparison.change().is_some_and(|c| c > threshold);
+            Some(comparison)
+        })
+        .collect();
+
+    comparisons.sort_by_key(|c| (c.day, c.step));
+    comparisons
+}
+
+/// The commit checked out in `dir`, suffixed with `-dirty` if there are uncommitted
+/// changes, `unknown` outside of a git repository
+pub fn commit(dir: &Path) -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    match (
+        git(&["rev-parse", "--short", "HEAD"]),
+        git(&["status", "--porcelain"]),
+    ) {
+        (Some(commit), Some(status)) if !status.is_empty() => format!("{}-dirty", commit),
+        (Some(commit), _) => commit,
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    const HISTORY: &str = "\
+        # time commit day step runs min median mean\n\
+        100 abc123 9 parse 10 900 1000 1100\n\
+        100 abc123 9 part1 10 1800 2000 2100\n\
+        100 abc123 9 part2 10 9000 10000 11000\n\
+        200 def456-dirty 9 parse 10 1000 1050 1100\n\
+        200 def456-dirty 9 part2 10 5000 5000 5000\n\
+        300 fed789 9 parse 10 1000 1200 1300\n\
+        300 fed789 9 part1 10 2000 2100 2200\n\
+        300 fed789 9 part2 10 4000 4000 4000\n";
+
+    #[test]
+    fn history() {
+        let history: History = HISTORY.parse().unwrap();
+
+        assert_eq!(
+            history.runs(),
+            vec![(100, "abc123"), (200, "def456-dirty"), (300, "fed789")]
+        );
+        assert_eq!(
+            history.baseline(None),
+            Ok(((200, "def456-dirty"), (300, "fed789")))
+        );
+        assert_eq!(
+            history.baseline(Some("abc")),
+            Ok(((100, "abc123"), (300, "fed789")))
+        );
+        assert!(history.baseline(Some("fed")).is_err());
+        assert!(History::default().baseline(None).is_err());
+
+        let measurement = &history.run(300, "fed789")[1];
+        assert_eq!(measurement.step, Step::Part(1));
+        assert_eq!(
+            measurement.to_string(),
+            "300 fed789 9 part1 10 2000 2100 2200"
+        );
+
+        assert!("100 abc 9 part1 10 1 2".parse::<History>().is_err());
+        assert!("100 abc 9 part 10 1 2 3".parse::<History>().is_err());
+
+        // two runs in the same second are told apart by their commit
+        let same_second: History = "100 abc 9 part1 10 1 2 3\n100 def 9 part1 10 4 5 6\n"
+            .parse()
+            .unwrap();
+        assert_eq!(same_second.runs(), vec![(100, "abc"), (100, "def")]);
+        assert_eq!(
+            same_second.run(100, "def")[0].median,
+            Duration::from_nanos(5)
+        );
+        assert!("100 abc 9 part1 10 1 2 3\n100 abc 9 part1 10 4 5 6\n"
+            .parse::<History>()
+            .is_err());
+    }
+
+    #[test]
+    fn regressions() {
+        let history: History = HISTORY.parse().unwrap();
+        let (baseline, current) = (history.run(100, "abc123"), history.run(300, "fed789"));
+
+        let comparisons = compare(&baseline, &current, 10.0);
+        let flagged: Vec<_> = comparisons
+            .iter()
+            .map(|c| (c.step, c.change().unwrap().round() as i64, c.regression))
+            .collect();
+
+        assert_eq!(
+            flagged,
+            vec![
+                (Step::Parse, 20, true),
+                (Step::Part(1), 5, false),
+                (Step::Part(2), -60, false),
+            ]
+        );
+
+        // only the steps measured in both runs are compared
+        assert_eq!(
+            compare(&history.run(200, "def456-dirty"), &current, 10.0).len(),
+            2
+        );
+
+        // a baseline without measurable time can't be compared
+        let zero: History = "100 abc 9 parse 10 0 0 0\n200 def 9 parse 10 1 1 1\n"
+            .parse()
+            .unwrap();
+        let comparisons = compare(&zero.run(100, "abc"), &zero.run(200, "def"), 10.0);
+        assert_eq!(comparisons[0].change(), None);
+        assert!(!comparisons[0].regression);
+    }
+
+    #[test]
+    fn record() {
+        let tmp = TempDir::new("bench");
+        let path = tmp.path().join(super::HISTORY);
+        let history: History = HISTORY.parse().unwrap();
+        let run: Vec<_> = history.run(100, "abc123").into_iter().cloned().collect();
+
+        History::record(&path, &run).unwrap();
+        // the run is recorded already
+        assert!(History::record(&path, &run[..1]).is_err());
+        let later: Vec<_> = history.run(300, "fed789").into_iter().cloned().collect();
+        History::record(&path, &later).unwrap();
+        assert_eq!(History::load(&path).unwrap().run(100, "abc123").len(), 3);
+        assert_eq!(History::load(&path).unwrap().runs().len(), 2);
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+/// Run `work` for every task on `jobs` threads.
+/// `done` is called on the calling thread with the results in the order of
+/// `tasks`, as soon as all results before it are available.
+pub fn run_ordered<T, R, F, C>(jobs: usize, tasks: Vec<T>, work: F, mut done: C)
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    C: FnMut(R),
+{
+    if jobs <= 1 {
+        tasks.into_iter().map(&work).for_each(done);
+        return;
+    }
+
+    let tasks = Mutex::new(tasks.into_iter().enumerate());
+    let (tx, rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let tx = tx.clone();
+            let (tasks, work) = (&tasks, &work);
+
+            scope.spawn(move || loop {
+                let task = tasks.lock().unwrap().next();
+                match task {
+                    Some((i, task)) => {
+                        if tx.send((i, work(task))).is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            });
+        }
+        drop(tx);
+
+        // results may arrive out of order, hold them back until it's their turn
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+
+        for (i, result) in rx {
+            pending.insert(i, result);
+
+            while let Some(result) = pending.remove(&next) {
+                done(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn ordered() {
+        for jobs in &[1, 4] {
+            let mut results = Vec::new();
+            let tasks: Vec<u64> = (0..20).collect();
+
+            run_ordered(
+                *jobs,
+                tasks,
+                |i| {
+                    // finish the later tasks first
+                    thread::sleep(Duration::from_millis(20 - i));
+                    i * 2
+                },
+                |result| results.push(result),
+            );
+
+            assert_eq!(results, (0..20).map(|i| i * 2).collect::<Vec<_>>());
+        }
+    }
+}
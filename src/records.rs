@@ -0,0 +1,44 @@
+//! Line based record files.
+//!
+//! Every record file (the answers manifest, the benchmark and submit histories)
+//! contains one whitespace separated record per line, empty lines and lines
+//! starting with `#` are ignored.
+
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The records of `s` together with their line number (starting at 1)
+pub fn records(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| (n + 1, line))
+}
+
+/// Read and parse the record file at `path`, `None` if it doesn't exist
+pub fn load<T>(path: &Path) -> Result<Option<T>, String>
+where
+    T: FromStr<Err = String>,
+{
+    match std::fs::read_to_string(path) {
+        Ok(records) => records
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Can't read {}: {}", path.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_comments_and_empty_lines() {
+        let records: Vec<_> = records("# comment\n\n1 1 42\n  \n  2 1 7  \n").collect();
+        assert_eq!(records, vec![(3, "1 1 42"), (5, "2 1 7")]);
+    }
+}
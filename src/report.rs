@@ -0,0 +1,521 @@
+use crate::alloc;
+use crate::answers;
+use crate::Answer;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "Invalid format '{}' (expected text, json or csv)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The part was run without an answers manifest
+    Ok,
+    Pass,
+    Fail,
+    Unknown,
+    /// The part has not been solved yet
+    Unsolved,
+    /// The part could not be run
+    Error,
+    /// The solver panicked
+    Panic,
+    /// The solver didn't finish in time
+    Timeout,
+}
+
+impl From<answers::Status> for Status {
+    fn from(status: answers::Status) -> Self {
+        match status {
+            answers::Status::Pass => Self::Pass,
+            answers::Status::Fail => Self::Fail,
+            answers::Status::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Ok => "ok",
+            Self::Pass => "pass",
+            Self::Fail => "fail",
+            Self::Unknown => "unknown",
+            Self::Unsolved => "unsolved",
+            Self::Error => "error",
+            Self::Panic => "panic",
+            Self::Timeout => "timeout",
+        })
+    }
+}
+
+/// The outcome of running a single part
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// The named input set, `None` for the default inputs
+    pub set: Option<String>,
+    pub day: usize,
+    pub part: usize,
+    pub answer: Option<Answer>,
+    pub expected: Option<Answer>,
+    /// Time spent parsing the input of the day, shared by all its parts
+    pub parse_elapsed: Option<Duration>,
+    /// Time spent solving the part, without parsing
+    pub elapsed: Option<Duration>,
+    /// Allocations while parsing, if counted
+    pub parse_alloc: Option<alloc::Stats>,
+    /// Allocations while solving the part, if counted
+    pub alloc: Option<alloc::Stats>,
+    pub status: Status,
+    /// Why the part could not be run
+    pub error: Option<String>,
+}
+
+/// Writes records in the selected format.
+/// Text and CSV are written as soon as a record is pushed,
+/// JSON is written as a single array by `finish`.
+pub struct Report<W: Write> {
+    format: Format,
+    out: W,
+    records: Vec<Record>,
+    /// Whether the parse time of the current day has been written
+    parse_written: bool,
+}
+
+/// `s` as a JSON string literal
+pub(crate) fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// The largest integer a JSON number can hold without losing precision (2^53 - 1)
+const JSON_MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Integers too big for a JSON number are written as strings
+fn json_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        None | Some(Answer::Unsolved) => "null".to_string(),
+        Some(Answer::Integer(i)) if i.unsigned_abs() <= JSON_MAX_SAFE_INTEGER => i.to_string(),
+        Some(Answer::Integer(i)) => json_string(&i.to_string()),
+        Some(Answer::BigUint(u)) => json_string(&u.to_string()),
+        Some(Answer::Text(s)) | Some(Answer::Multiline(s)) => json_string(s),
+    }
+}
+
+fn json_alloc(stats: Option<alloc::Stats>) -> String {
+    stats.map_or("null".to_string(), |s| {
+        format!(
+            "{{\"count\": {}, \"bytes\": {}, \"peak\": {}}}",
+            s.count, s.bytes, s.peak
+        )
+    })
+}
+
+/// The allocation columns of the text format, empty if allocations aren't counted
+fn text_alloc(stats: Option<alloc::Stats>) -> String {
+    stats.map_or(String::new(), |s| {
+        format!(
+            " {:>7} allocs {:>10} B {:>10} B peak",
+            s.count, s.bytes, s.peak
+        )
+    })
+}
+
+/// An unsolved answer is an empty field like a missing one
+fn csv_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        None | Some(Answer::Unsolved) => String::new(),
+        Some(answer) => csv_field(&answer.to_string()),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl<W: Write> Report<W> {
+    pub fn new(format: Format, mut out: W) -> io::Result<Self> {
+        if format == Format::Csv {
+            writeln!(
+                out,
+                "set,day,part,answer,parse_ns,elapsed_ns,\
+                 parse_allocs,parse_alloc_bytes,parse_peak_bytes,allocs,alloc_bytes,peak_bytes,\
+                 status,expected,error"
+            )?;
+        }
+
+        Ok(Self {
+            format,
+            out,
+            records: Vec::new(),
+            parse_written: false,
+        })
+    }
+
+    pub fn push(&mut self, record: Record) -> io::Result<()> {
+        match self.format {
+            Format::Text => self.write_text(&record)?,
+            Format::Csv => self.write_csv(&record)?,
+            Format::Json => (),
+        }
+
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Called before the records of the named input set `set` are pushed
+    pub fn begin_set(&mut self, set: &str) -> io::Result<()> {
+        if self.format == Format::Text {
+            writeln!(self.out, "[{}]", set)?;
+        }
+        Ok(())
+    }
+
+    /// Called after all parts of a day have been pushed
+    pub fn end_day(&mut self) -> io::Result<()> {
+        if self.format == Format::Text {
+            writeln!(self.out)?;
+        }
+        self.parse_written = false;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<Vec<Record>> {
+        if self.format == Format::Json {
+            self.write_json()?;
+        }
+        self.out.flush()?;
+
+        Ok(self.records)
+    }
+
+    fn write_text(&mut self, record: &Record) -> io::Result<()> {
+        // the parse time is written once, before the first part of a day
+        if let (false, Some(elapsed)) = (self.parse_written, record.parse_elapsed) {
+            writeln!(
+                self.out,
+                "day{:0>2}-parse {:>9} us{}",
+                record.day,
+                elapsed.as_micros(),
+                text_alloc(record.parse_alloc)
+            )?;
+            self.parse_written = true;
+        }
+
+        // multi-line answers are written below the result line
+        let (answer, lines) = match &record.answer {
+            Some(Answer::Multiline(lines)) => (String::new(), Some(lines)),
+            Some(Answer::Unsolved) | None => (String::new(), None),
+            Some(answer) => (answer.to_string(), None),
+        };
+
+        write!(
+            self.out,
+            "day{:0>2}-part{} {:>9} us{} {:>12}",
+            record.day,
+            record.part,
+            record
+                .elapsed
+                .map_or("-".to_string(), |e| e.as_micros().to_string()),
+            text_alloc(record.alloc),
+            answer
+        )?;
+
+        match record.status {
+            Status::Ok => (),
+            status => write!(self.out, " {}", status.to_string().to_uppercase())?,
+        }
+
+        if let Some(error) = &record.error {
+            write!(self.out, " ({})", error)?;
+        }
+
+        if let (Status::Fail, Some(expected)) = (record.status, &record.expected) {
+            write!(self.out, " (expected {})", expected)?;
+        }
+
+        writeln!(self.out)?;
+
+        for line in lines.iter().flat_map(|lines| lines.lines()) {
+            writeln!(self.out, "    {}", line)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_csv(&mut self, record: &Record) -> io::Result<()> {
+        let alloc = |stats: Option<alloc::Stats>| {
+            stats.map_or(",,".to_string(), |s| {
+                format!("{},{},{}", s.count, s.bytes, s.peak)
+            })
+        };
+
+        writeln!(
+            self.out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(record.set.as_deref().unwrap_or_default()),
+            record.day,
+            record.part,
+            csv_answer(&record.answer),
+            record
+                .parse_elapsed
+                .map(|e| e.as_nanos().to_string())
+                .unwrap_or_default(),
+            record
+                .elapsed
+                .map(|e| e.as_nanos().to_string())
+                .unwrap_or_default(),
+            alloc(record.parse_alloc),
+            alloc(record.alloc),
+            record.status,
+            csv_answer(&record.expected),
+            csv_field(record.error.as_deref().unwrap_or_default()),
+        )
+    }
+
+    fn write_json(&mut self) -> io::Result<()> {
+        let optional = |s: &Option<String>| s.as_deref().map_or("null".to_string(), json_string);
+        let nanos =
+            |d: Option<Duration>| d.map_or("null".to_string(), |d| d.as_nanos().to_string());
+
+        writeln!(self.out, "[")?;
+        for (i, record) in self.records.iter().enumerate() {
+            writeln!(
+                self.out,
+                "  {{\"set\": {}, \"day\": {}, \"part\": {}, \"answer\": {}, \"parse_ns\": {}, \"elapsed_ns\": {}, \"parse_alloc\": {}, \"alloc\": {}, \"status\": \"{}\", \"expected\": {}, \"error\": {}}}{}",
+                optional(&record.set),
+                record.day,
+                record.part,
+                json_answer(&record.answer),
+                nanos(record.parse_elapsed),
+                nanos(record.elapsed),
+                json_alloc(record.parse_alloc),
+                json_alloc(record.alloc),
+                record.status,
+                json_answer(&record.expected),
+                optional(&record.error),
+                if i + 1 < self.records.len() { "," } else { "" }
+            )?;
+        }
+        writeln!(self.out, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                set: None,
+                day: 7,
+                part: 1,
+                answer: Some(151usize.into()),
+                expected: Some(151usize.into()),
+                parse_elapsed: Some(Duration::from_micros(300)),
+                elapsed: Some(Duration::from_micros(1500)),
+                parse_alloc: Some(alloc::Stats {
+                    count: 3,
+                    bytes: 4096,
+                    peak: 4000,
+                }),
+                alloc: Some(alloc::Stats {
+                    count: 1,
+                    bytes: 16,
+                    peak: 16,
+                }),
+                status: Status::Pass,
+                error: None,
+            },
+            Record {
+                set: None,
+                day: 7,
+                part: 2,
+                answer: Some("a \"quoted\", answer".into()),
+                expected: None,
+                parse_elapsed: Some(Duration::from_micros(300)),
+                elapsed: Some(Duration::from_nanos(42)),
+                parse_alloc: None,
+                alloc: None,
+                status: Status::Unknown,
+                error: None,
+            },
+            Record {
+                set: Some("alice".to_string()),
+                day: 8,
+                part: 1,
+                answer: None,
+                expected: None,
+                parse_elapsed: None,
+                elapsed: None,
+                parse_alloc: None,
+                alloc: None,
+                status: Status::Error,
+                error: Some("No solution found".to_string()),
+            },
+        ]
+    }
+
+    fn render(format: Format) -> String {
+        let mut out = Vec::new();
+        let mut report = Report::new(format, &mut out).unwrap();
+        for record in records() {
+            if record.day == 8 {
+                report.end_day().unwrap();
+                report.begin_set("alice").unwrap();
+            }
+            report.push(record).unwrap();
+        }
+        report.finish().unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            render(Format::Text),
+            "day07-parse       300 us       3 allocs       4096 B       4000 B peak\n\
+             day07-part1      1500 us       1 allocs         16 B         16 B peak          151 PASS\n\
+             day07-part2         0 us a \"quoted\", answer UNKNOWN\n\
+             \n\
+             [alice]\n\
+             day08-part1         - us              ERROR (No solution found)\n"
+        );
+    }
+
+    #[test]
+    fn multiline() {
+        let mut out = Vec::new();
+        let mut report = Report::new(Format::Text, &mut out).unwrap();
+        report
+            .push(Record {
+                set: None,
+                day: 1,
+                part: 2,
+                answer: Some("#.#\n.#.".into()),
+                expected: None,
+                parse_elapsed: None,
+                elapsed: Some(Duration::from_micros(7)),
+                parse_alloc: None,
+                alloc: None,
+                status: Status::Ok,
+                error: None,
+            })
+            .unwrap();
+        report.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "day01-part2         7 us             \n    #.#\n    .#.\n"
+        );
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            render(Format::Csv),
+            "set,day,part,answer,parse_ns,elapsed_ns,\
+             parse_allocs,parse_alloc_bytes,parse_peak_bytes,allocs,alloc_bytes,peak_bytes,\
+             status,expected,error\n\
+             ,7,1,151,300000,1500000,3,4096,4000,1,16,16,pass,151,\n\
+             ,7,2,\"a \"\"quoted\"\", answer\",300000,42,,,,,,,unknown,,\n\
+             alice,8,1,,,,,,,,,,error,,No solution found\n"
+        );
+
+        let mut out = Vec::new();
+        let mut report = Report::new(Format::Csv, &mut out).unwrap();
+        report
+            .push(Record {
+                set: None,
+                day: 9,
+                part: 2,
+                answer: Some(Answer::Unsolved),
+                expected: None,
+                parse_elapsed: None,
+                elapsed: Some(Duration::from_nanos(5)),
+                parse_alloc: None,
+                alloc: None,
+                status: Status::Unsolved,
+                error: None,
+            })
+            .unwrap();
+        report.finish().unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("\n,9,2,,,5,,,,,,,unsolved,,\n"));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            render(Format::Json),
+            "[\n  \
+             {\"set\": null, \"day\": 7, \"part\": 1, \"answer\": 151, \"parse_ns\": 300000, \"elapsed_ns\": 1500000, \"parse_alloc\": {\"count\": 3, \"bytes\": 4096, \"peak\": 4000}, \"alloc\": {\"count\": 1, \"bytes\": 16, \"peak\": 16}, \"status\": \"pass\", \"expected\": 151, \"error\": null},\n  \
+             {\"set\": null, \"day\": 7, \"part\": 2, \"answer\": \"a \\\"quoted\\\", answer\", \"parse_ns\": 300000, \"elapsed_ns\": 42, \"parse_alloc\": null, \"alloc\": null, \"status\": \"unknown\", \"expected\": null, \"error\": null},\n  \
+             {\"set\": \"alice\", \"day\": 8, \"part\": 1, \"answer\": null, \"parse_ns\": null, \"elapsed_ns\": null, \"parse_alloc\": null, \"alloc\": null, \"status\": \"error\", \"expected\": null, \"error\": \"No solution found\"}\n\
+             ]\n"
+        );
+    }
+    #[test]
+    fn json_big_integers() {
+        let max = (1i64 << 53) - 1;
+        assert_eq!(json_answer(&Some(Answer::Integer(max))), max.to_string());
+        assert_eq!(
+            json_answer(&Some(Answer::Integer(-max))),
+            (-max).to_string()
+        );
+        assert_eq!(
+            json_answer(&Some(Answer::Integer(max + 1))),
+            "\"9007199254740992\""
+        );
+        assert_eq!(
+            json_answer(&Some(Answer::Integer(i64::MIN))),
+            "\"-9223372036854775808\""
+        );
+        assert_eq!(
+            json_answer(&Some(Answer::from(u64::MAX))),
+            "\"18446744073709551615\""
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The skeleton of a new day module, see `module`
+const TEMPLATE: &str = r#"use super::{Answer, Day as DayTrait, Input, Meta, SolveError};
+
+pub const META: Meta = Meta {
+    day: {day},
+    title: "{title}",
+    input: include_str!("inputs/{day}"),
+};
+
+pub struct Day;
+
+impl DayTrait for Day {
+    type Parsed<'i> = Vec<&'i str>;
+
+    fn parse<'i>(&self, input: &Input<'i>) -> Result<Self::Parsed<'i>, SolveError> {
+        Ok(input.lines().filter(|line| !line.is_empty()).collect())
+    }
+
+    fn part1(&self, _lines: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part2(&self, _lines: &Self::Parsed<'_>) -> Result<Answer, SolveError> {
+        Ok(Answer::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "";
+
+    #[test]
+    fn samples_part1() {
+        let parsed = Day.parse(&Input(SAMPLE)).unwrap();
+        assert_eq!(Day.part1(&parsed), Ok(Answer::Unsolved));
+    }
+}
+"#;
+
+/// The examples file of a new day, see `examples`
+const EXAMPLES: &str = "\
+# Examples of day {day}, see src/examples.rs for the format and list them in tests/examples.rs
+# == sample
+# part1 <answer>
+# part2 <answer>
+# --
+# <input>
+";
+
+/// The source of the module `day{day}`
+pub fn module(day: usize, title: &str) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Add `day{day}` to the `days!` list of `lib` (the source of `lib.rs`),
+/// the list is kept ordered by day
+pub fn register(lib: &str, day: usize) -> Result<String, String> {
+    const MISSING: &str = "Can't find the days! list";
+
+    let start = lib.find("\ndays! {").ok_or(MISSING)? + "\ndays! {".len();
+    let end = start + lib[start..].find('}').ok_or(MISSING)?;
+
+    let mut days = lib[start..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|module| !module.is_empty())
+        .map(|module| {
+            module
+                .strip_prefix("day")
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| format!("Unexpected module '{}' in the days! list", module))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if days.contains(&day) {
+        return Err(format!("Day {} is registered already", day));
+    }
+    days.push(day);
+    days.sort_unstable();
+
+    let list: String = days.iter().map(|n| format!("    day{},\n", n)).collect();
+    Ok(format!("{}\n{}{}", &lib[..start], list, &lib[end..]))
+}
+
+fn create_new(path: &Path, contents: &str) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => format!("{} exists already", path.display()),
+            _ => format!("Can't create {}: {}", path.display(), e),
+        })?;
+
+    file.write_all(contents.as_bytes())
+        .map_err(|e| format!("Can't write {}: {}", path.display(), e))
+}
+
+/// Generate the module, an empty input and an examples skeleton for `day` in the crate at `root`
+/// and register the module in `src/lib.rs`, returns the created files.
+/// Existing files are never overwritten.
+pub fn create(root: &Path, day: usize, title: &str) -> Result<Vec<PathBuf>, String> {
+    let src = root.join("src");
+    let lib_path = src.join("lib.rs");
+    let module_path = src.join(format!("day{}.rs", day));
+    let input_path = src.join("inputs").join(day.to_string());
+    let examples_path = src.join("examples").join(day.to_string());
+
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|e| format!("Can't read {}: {}", lib_path.display(), e))?;
+    let lib = register(&lib, day)?;
+
+    for path in &[&module_path, &input_path, &examples_path] {
+        if path.exists() {
+            return Err(format!("{} exists already", path.display()));
+        }
+    }
+
+    create_new(&module_path, &module(day, title))?;
+    create_new(&input_path, "")?;
+    create_new(&examples_path, &EXAMPLES.replace("{day}", &day.to_string()))?;
+    fs::write(&lib_path, lib).map_err(|e| format!("Can't write {}: {}", lib_path.display(), e))?;
+
+    Ok(vec![module_path, input_path, examples_path, lib_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    const LIB: &str = "pub mod answer;\n\ndays! {\n    day1,\n    day3,\n}\n\npub fn day() {}\n";
+
+    #[test]
+    fn registry() {
+        assert_eq!(
+            register(LIB, 2).unwrap(),
+            "pub mod answer;\n\ndays! {\n    day1,\n    day2,\n    day3,\n}\n\npub fn day() {}\n"
+        );
+        assert_eq!(
+            register("pub mod answer;\n", 1).unwrap_err(),
+            "Can't find the days! list"
+        );
+        assert!(register("\ndays! {}\n", 1)
+            .unwrap()
+            .ends_with("days! {\n    day1,\n}\n"));
+        assert!(register(LIB, 3).is_err());
+    }
+
+    #[test]
+    fn template() {
+        let module = module(10, "Adapter \"Array\"");
+        assert!(module.contains("    day: 10,\n"));
+        assert!(module.contains("    title: \"Adapter \\\"Array\\\"\",\n"));
+        assert!(module.contains("include_str!(\"inputs/10\")"));
+    }
+
+    #[test]
+    fn files() {
+        let tmp = TempDir::new("scaffold");
+        let root = tmp.path();
+        fs::create_dir_all(root.join("src/inputs")).unwrap();
+        fs::create_dir_all(root.join("src/examples")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        let created = create(root, 2, "Password Philosophy").unwrap();
+        assert_eq!(created.len(), 4);
+        assert_eq!(
+            crate::examples::load(&root.join("src/examples"), 2),
+            Ok(Vec::new())
+        );
+        assert_eq!(fs::read_to_string(root.join("src/inputs/2")).unwrap(), "");
+        assert!(fs::read_to_string(root.join("src/day2.rs"))
+            .unwrap()
+            .contains("Password Philosophy"));
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("    day2,\n"));
+
+        // neither the module nor the registry are touched a second time
+        assert!(create(root, 2, "Again").is_err());
+        fs::write(root.join("src/day4.rs"), "// mine").unwrap();
+        assert!(create(root, 4, "Passport Processing").is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/day4.rs")).unwrap(),
+            "// mine"
+        );
+        assert!(!fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("day4"));
+    }
+}
@@ -0,0 +1,392 @@
+use crate::records;
+use crate::Answer;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Name of the submission history inside an inputs directory
+pub const HISTORY: &str = "history";
+
+/// Name of the submission history in the crate directory, used for the embedded inputs
+pub const CRATE_HISTORY: &str = "submit-history";
+
+/// How the server judged a submitted answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction
+    Wrong,
+    /// The answer was not checked, the next one may be submitted after the given time
+    Wait(Duration),
+    /// The part has been solved before, the answer was not checked
+    Solved,
+    /// The response could not be understood
+    Unknown,
+}
+
+impl Verdict {
+    /// Read the verdict from the page the server answered with
+    pub fn from_response(page: &str) -> Self {
+        if page.contains("That's the right answer") {
+            Self::Correct
+        } else if page.contains("answer is too high") {
+            Self::TooHigh
+        } else if page.contains("answer is too low") {
+            Self::TooLow
+        } else if page.contains("That's not the right answer") {
+            Self::Wrong
+        } else if page.contains("You gave an answer too recently") {
+            // "You have 1m 7s left to wait."
+            let wait = page
+                .split("You have ")
+                .nth(1)
+                .and_then(|rest| rest.split(" left to wait").next())
+                .map_or(60, |wait| {
+                    wait.split_whitespace()
+                        .filter_map(|part| {
+                            let (n, unit) = if let Some(n) = part.strip_suffix('s') {
+                                (n, 1)
+                            } else if let Some(n) = part.strip_suffix('m') {
+                                (n, 60)
+                            } else {
+                                (part.strip_suffix('h')?, 3600)
+                            };
+                            n.parse::<u64>().ok().map(|n| n * unit)
+                        })
+                        .sum()
+                });
+            Self::Wait(Duration::from_secs(wait))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Self::Solved
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Whether the answer is known to be wrong
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => f.pad("correct"),
+            Self::TooHigh => f.pad("too-high"),
+            Self::TooLow => f.pad("too-low"),
+            Self::Wrong => f.pad("wrong"),
+            Self::Wait(wait) => f.pad(&format!("wait-{}s", wait.as_secs())),
+            Self::Solved => f.pad("solved"),
+            Self::Unknown => f.pad("unknown"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Self::Correct),
+            "too-high" => Ok(Self::TooHigh),
+            "too-low" => Ok(Self::TooLow),
+            "wrong" => Ok(Self::Wrong),
+            "solved" => Ok(Self::Solved),
+            "unknown" => Ok(Self::Unknown),
+            _ => s
+                .strip_prefix("wait-")
+                .and_then(|wait| wait.strip_suffix('s'))
+                .and_then(|wait| wait.parse().ok())
+                .map(|wait| Self::Wait(Duration::from_secs(wait)))
+                .ok_or_else(|| format!("Invalid verdict '{}'", s)),
+        }
+    }
+}
+
+/// A single submitted answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    /// When the answer was submitted, in seconds since the unix epoch
+    pub time: u64,
+    pub day: usize,
+    pub part: usize,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.time,
+            self.day,
+            self.part,
+            self.verdict,
+            self.answer.to_string().replace('\n', "\\n")
+        )
+    }
+}
+
+/// Every answer submitted so far.
+///
+/// The history is a record file (see [`records`]) with one
+/// `<time> <day> <part> <verdict> <answer>` record per attempt.
+/// Line breaks in multi-line answers are written as `\n`, like in the answers manifest.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History(Vec<Attempt>);
+
+impl FromStr for History {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut attempts = Vec::new();
+
+        for (n, line) in records::records(s) {
+            let invalid = || {
+                format!(
+                    "line {}: expected '<time> <day> <part> <verdict> <answer>'",
+                    n
+                )
+            };
+            let fields: Vec<_> = line.splitn(5, char::is_whitespace).collect();
+            let (time, day, part, verdict, answer) = match &fields[..] {
+                [time, day, part, verdict, answer] => (time, day, part, verdict, answer),
+                _ => return Err(invalid()),
+            };
+
+            attempts.push(Attempt {
+                time: time.parse().map_err(|_| invalid())?,
+                day: day.parse().map_err(|_| invalid())?,
+                part: part.parse().map_err(|_| invalid())?,
+                verdict: verdict
+                    .parse()
+                    .map_err(|e| format!("line {}: {}", n + 1, e))?,
+                answer: answer
+                    .trim()
+                    .replace("\\n", "\n")
+                    .parse()
+                    .unwrap_or_else(|e| match e {}),
+            });
+        }
+
+        Ok(History(attempts))
+    }
+}
+
+/// The numeric value of integer answers, to compare them with the bounds
+fn number(answer: &Answer) -> Option<i128> {
+    match answer {
+        Answer::Integer(i) => Some(i128::from(*i)),
+        Answer::BigUint(u) => i128::try_from(*u).ok(),
+        _ => None,
+    }
+}
+
+impl History {
+    /// Load the history from `path`, a missing file is an empty history
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(records::load(path)?.unwrap_or_default())
+    }
+
+    /// Add `attempt` to the history and append it to the file at `path`
+    pub fn record(&mut self, path: &Path, attempt: Attempt) -> Result<(), String> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{}", attempt))
+            .map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+
+        self.0.push(attempt);
+        Ok(())
+    }
+
+    pub fn attempts(&self, day: usize, part: usize) -> impl Iterator<Item = &Attempt> {
+        self.0
+            .iter()
+            .filter(move |attempt| attempt.day == day && attempt.part == part)
+    }
+
+    /// Why `answer` must not be submitted at `now` (seconds since the unix epoch),
+    /// `None` if it may be submitted
+    pub fn refuse(&self, day: usize, part: usize, answer: &Answer, now: u64) -> Option<String> {
+        if !answer.is_solved() {
+            return Some(format!("Day {} part {} is not solved yet", day, part));
+        }
+
+        // the server doesn't accept any answer until the wait is over
+        let wait_until = self
+            .0
+            .iter()
+            .filter_map(|attempt| match attempt.verdict {
+                Verdict::Wait(wait) => Some(attempt.time + wait.as_secs()),
+                _ => None,
+            })
+            .max();
+        if let Some(until) = wait_until.filter(|until| *until > now) {
+            return Some(format!("Wait {}s before submitting again", until - now));
+        }
+
+        let (mut low, mut high) = (None, None);
+
+        for attempt in self.attempts(day, part) {
+            match attempt.verdict {
+                Verdict::Correct | Verdict::Solved if &attempt.answer == answer => {
+                    return Some(format!("{} has been accepted already", answer));
+                }
+                Verdict::Correct => {
+                    return Some(format!(
+                        "Day {} part {} has been solved already, the answer was {}",
+                        day, part, attempt.answer
+                    ));
+                }
+                verdict if verdict.is_wrong() && &attempt.answer == answer => {
+                    return Some(format!(
+                        "{} was submitted before and is {}",
+                        answer, verdict
+                    ));
+                }
+                Verdict::TooLow => low = low.max(number(&attempt.answer)),
+                Verdict::TooHigh => {
+                    high = match (high, number(&attempt.answer)) {
+                        (Some(high), Some(n)) => Some(n.min(high)),
+                        (high, n) => high.or(n),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        match number(answer) {
+            Some(n) if low.is_some_and(|low| n <= low) => Some(format!(
+                "{} is too low, {} was too low already",
+                answer,
+                low.unwrap()
+            )),
+            Some(n) if high.is_some_and(|high| n >= high) => Some(format!(
+                "{} is too high, {} was too high already",
+                answer,
+                high.unwrap()
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+    use std::fs;
+
+    #[test]
+    fn verdicts() {
+        let page = |text| format!("<main><article><p>{}</p></article></main>", text);
+
+        assert_eq!(
+            Verdict::from_response(&page(
+                "That's the right answer!  You are one gold star closer"
+            )),
+            Verdict::Correct
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "That's not the right answer; your answer is too high.  Please wait one minute"
+            )),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            Verdict::from_response(&page("That's not the right answer.  If you're stuck")),
+            Verdict::Wrong
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 1m 7s left to wait."
+            )),
+            Verdict::Wait(Duration::from_secs(67))
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "You gave an answer too recently.  You have 1h 2é 5s left to wait."
+            )),
+            Verdict::Wait(Duration::from_secs(3605))
+        );
+        assert_eq!(
+            Verdict::from_response(&page("You don't seem to be solving the right level.")),
+            Verdict::Solved
+        );
+        assert_eq!(Verdict::from_response("<html></html>"), Verdict::Unknown);
+
+        for verdict in &[Verdict::TooLow, Verdict::Wait(Duration::from_secs(30))] {
+            assert_eq!(verdict.to_string().parse(), Ok(*verdict));
+        }
+    }
+
+    #[test]
+    fn refuse() {
+        let history: History = "\
+            # time day part verdict answer\n\
+            100 1 1 too-low 10\n\
+            200 1 1 too-high 50\n\
+            300 1 1 too-high 40\n\
+            400 1 1 wrong abc\n\
+            500 2 1 correct 620\n\
+            600 2 2 wait-60s 727\n"
+            .parse()
+            .unwrap();
+
+        let refuse = |day, part, answer: Answer, now| history.refuse(day, part, &answer, now);
+
+        assert_eq!(refuse(1, 1, 20.into(), 1000), None);
+        assert!(refuse(1, 1, 10.into(), 1000).is_some());
+        assert!(refuse(1, 1, 5.into(), 1000).is_some());
+        assert!(refuse(1, 1, 40.into(), 1000).is_some());
+        assert!(refuse(1, 1, 45.into(), 1000).is_some());
+        assert!(refuse(1, 1, "abc".into(), 1000).is_some());
+        assert!(refuse(1, 1, Answer::Unsolved, 1000).is_some());
+        assert!(refuse(2, 1, 620.into(), 1000).is_some());
+        assert!(refuse(2, 1, 621.into(), 1000).is_some());
+        assert_eq!(refuse(2, 2, 727.into(), 1000), None);
+        assert_eq!(
+            refuse(1, 2, 1.into(), 630),
+            Some("Wait 30s before submitting again".to_string())
+        );
+    }
+
+    #[test]
+    fn record() {
+        let tmp = TempDir::new("history");
+        let path = tmp.path().join(HISTORY);
+        let attempt = Attempt {
+            time: 1_607_000_000,
+            day: 8,
+            part: 2,
+            answer: "#.\n.#".into(),
+            verdict: Verdict::TooHigh,
+        };
+
+        let mut history = History::load(&path).unwrap();
+        history.record(&path, attempt.clone()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "1607000000 8 2 too-high #.\\n.#\n"
+        );
+        assert_eq!(History::load(&path).unwrap(), History(vec![attempt]));
+    }
+}
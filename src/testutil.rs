@@ -0,0 +1,24 @@
+use std::path::{Path, PathBuf};
+
+/// A temporary directory which is removed again when dropped
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create a fresh directory unique to this process and `name`
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("aoc-rs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
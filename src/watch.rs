@@ -0,0 +1,119 @@
+//! Support for `watch`, which re-runs a day whenever one of its files changes.
+//!
+//! Files are polled for changes of their modification time, which doesn't need any
+//! platform specific notification mechanism.
+
+use crate::Answer;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Polls the modification times of a set of files
+#[derive(Clone, Debug)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+impl Watcher {
+    /// Watch `paths`, their current state counts as unchanged
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            files: paths
+                .into_iter()
+                .map(|path| {
+                    let modified = modified(&path);
+                    (path, modified)
+                })
+                .collect(),
+        }
+    }
+
+    /// The watched files which were modified, created or removed since the last call
+    pub fn changed(&mut self) -> Vec<&Path> {
+        self.files
+            .iter_mut()
+            .filter_map(|(path, last)| {
+                let modified = modified(path);
+                if modified == *last {
+                    return None;
+                }
+                *last = modified;
+                Some(path.as_path())
+            })
+            .collect()
+    }
+}
+
+/// How `current` differs from the `previous` answer of a part: the removed lines
+/// prefixed with `-` and the added lines prefixed with `+`, empty if nothing changed.
+/// Only the differing lines of multiline answers are shown.
+pub fn diff(previous: &Answer, current: &Answer) -> Vec<String> {
+    if previous == current {
+        return Vec::new();
+    }
+
+    let (previous, current) = (previous.to_string(), current.to_string());
+    let (previous, current): (Vec<_>, Vec<_>) =
+        (previous.lines().collect(), current.lines().collect());
+
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for i in 0..previous.len().max(current.len()) {
+        match (previous.get(i), current.get(i)) {
+            (old, new) if old == new => continue,
+            (old, new) => {
+                removed.extend(old.map(|line| format!("- {}", line)));
+                added.extend(new.map(|line| format!("+ {}", line)));
+            }
+        }
+    }
+
+    removed.extend(added);
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+    use std::time::Duration;
+
+    #[test]
+    fn changes() {
+        let tmp = TempDir::new("watch");
+        let dir = tmp.path();
+        let (input, examples) = (dir.join("8"), dir.join("examples"));
+        fs::write(&input, "nop +0\n").unwrap();
+
+        let mut watcher = Watcher::new(vec![input.clone(), examples.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        // pretend the input was seen earlier, the resolution of the file system may be coarse
+        watcher.files[0].1 = modified(&input).map(|time| time - Duration::from_secs(1));
+        assert_eq!(watcher.changed(), vec![input.as_path()]);
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&examples, "== sample\n").unwrap();
+        assert_eq!(watcher.changed(), vec![examples.as_path()]);
+        fs::remove_file(&examples).unwrap();
+        assert_eq!(watcher.changed(), vec![examples.as_path()]);
+    }
+
+    #[test]
+    fn answers() {
+        assert!(diff(&42.into(), &42.into()).is_empty());
+        assert_eq!(diff(&41.into(), &42.into()), vec!["- 41", "+ 42"]);
+        assert_eq!(
+            diff(&"#.\n.#\n##".into(), &"#.\n##".into()),
+            vec!["- .#", "- ##", "+ ##"]
+        );
+        assert_eq!(
+            diff(&Answer::Unsolved, &"abc".into()),
+            vec!["- unsolved", "+ abc"]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! The examples of every day in `src/examples`, see `aoc_rs::examples`
+
+use std::path::Path;
+
+/// One test per example, given as `day => { test_name: "example name", ... }`
+macro_rules! examples {
+    ($($day:literal => { $($test:ident: $name:literal),* $(,)? })*) => {
+        $($(
+            #[test]
+            fn $test() {
+                let examples = include_str!(concat!("../src/examples/", $day));
+                if let Err(e) = aoc_rs::examples::run($day, $name, examples) {
+                    panic!("{}", e);
+                }
+            }
+        )*)*
+
+        /// The examples with a test, as (day, name)
+        const LISTED: &[(usize, &str)] = &[$($(($day, $name),)*)*];
+    };
+}
+
+examples! {
+    1 => { day01_sample: "sample" }
+    2 => { day02_sample: "sample" }
+    3 => { day03_sample: "sample" }
+    4 => {
+        day04_sample: "sample",
+        day04_invalid: "invalid",
+        day04_valid: "valid",
+    }
+    5 => { day05_sample: "sample" }
+    6 => { day06_sample: "sample" }
+    7 => {
+        day07_sample: "sample",
+        day07_nested: "nested",
+    }
+    8 => { day08_sample: "sample" }
+    9 => { day09_preamble: "preamble" }
+}
+
+#[test]
+fn every_example_is_listed() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/examples");
+    let mut examples = Vec::new();
+
+    for entry in aoc_rs::days() {
+        for example in aoc_rs::examples::load(&dir, entry.day).unwrap() {
+            examples.push((entry.day, example.name));
+        }
+    }
+
+    let mut listed: Vec<_> = LISTED
+        .iter()
+        .map(|&(day, name)| (day, name.to_string()))
+        .collect();
+    listed.sort();
+    examples.sort();
+
+    assert_eq!(listed, examples, "add every example to tests/examples.rs");
+}